@@ -0,0 +1,24 @@
+//! Asynchronous functions
+
+use crate::common::is_scan_address;
+use core::ops::RangeInclusive;
+use embedded_hal_async::i2c::I2c;
+use heapless::Vec;
+
+/// Scan the bus for responding devices
+///
+/// This is the asynchronous version of [`scan()`](crate::scan).
+/// A zero-length write is sent to each address in the range and the addresses
+/// acknowledging it are returned.
+/// Note that any responding device will be reported, not only MLX90614/MLX90615 ones.
+/// Only the addresses 0x08 to 0x77 are probed, the reserved ones are skipped.
+pub async fn scan<I2C: I2c>(i2c: &mut I2C, range: RangeInclusive<u8>) -> Vec<u8, 128> {
+    let mut found = Vec::new();
+    for address in range.filter(|a| is_scan_address(*a)) {
+        if i2c.write(address, &[]).await.is_ok() {
+            // cannot fail as there are at most 112 valid addresses
+            let _ = found.push(address);
+        }
+    }
+    found
+}
@@ -1,17 +1,98 @@
 use crate::{
     ic,
     register_access::{mlx90614, mlx90615},
-    Error, Mlx9061x, SlaveAddr,
+    Error, Material, Mlx9061x, SlaveAddr, Temperature,
 };
+use core::ops::RangeInclusive;
 use embedded_hal::{delay::DelayNs, i2c::I2c};
+use heapless::Vec;
 
 impl<I2C, IC> Mlx9061x<I2C, IC> {
     /// Destroy driver instance, return I²C bus.
     pub fn destroy(self) -> I2C {
         self.i2c
     }
+
+    /// Update the cached emissivity after writing `raw` to the emissivity register
+    ///
+    /// The cache is cleared if the write failed, as the register content is unknown.
+    pub(crate) fn update_cached_emissivity<E>(
+        &mut self,
+        raw: u16,
+        result: Result<(), Error<E>>,
+    ) -> Result<(), Error<E>> {
+        self.emissivity = result.as_ref().ok().map(|_| raw);
+        result
+    }
+
+    /// Clear all values cached by the driver
+    ///
+    /// This clears the cached device ID, the cached ambient temperature range,
+    /// the cached emissivity, the raw IR scale, the last reading used for change detection,
+    /// the peak held by `object1_peak_hold()`, the samples of the rolling median
+    /// filter and the exponential moving average without communicating with the device.
+    /// The sample index and the output state of `thermostat_object1()` are not reset.
+    pub fn reset_cache(&mut self) {
+        self.device_id = None;
+        self.last_object1 = None;
+        self.peak_object1 = None;
+        self.ta_range = None;
+        self.emissivity = None;
+        self.ir_scale = None;
+        self.filter_samples.clear();
+        self.ema_object1 = None;
+    }
+
+    /// Enable or disable the EEPROM write protection
+    ///
+    /// While enabled, all methods writing to the EEPROM return `Error::WriteProtected`
+    /// without communicating with the device.
+    ///
+    /// This is only a software guard in the driver, the device EEPROM itself
+    /// is not protected.
+    pub fn set_eeprom_write_protect(&mut self, on: bool) {
+        self.eeprom_write_protected = on;
+    }
 }
 
+/// Scan the bus for responding devices
+///
+/// A zero-length write is sent to each address in the range and the addresses
+/// acknowledging it are returned.
+/// Note that any responding device will be reported, not only MLX90614/MLX90615 ones.
+/// Only the addresses 0x08 to 0x77 are probed, the reserved ones are skipped.
+pub fn scan<I2C: I2c>(i2c: &mut I2C, range: RangeInclusive<u8>) -> Vec<u8, 128> {
+    let mut found = Vec::new();
+    for address in range.filter(|a| is_scan_address(*a)) {
+        if i2c.write(address, &[]).is_ok() {
+            // cannot fail as there are at most 112 valid addresses
+            let _ = found.push(address);
+        }
+    }
+    found
+}
+
+/// Whether an address is probed by the bus scan
+///
+/// The reserved I²C addresses 0x00 to 0x07 and 0x78 to 0x7F are skipped
+/// like `i2cdetect` does.
+pub(crate) fn is_scan_address(address: u8) -> bool {
+    (0x08..=0x77).contains(&address)
+}
+
+/// Send an I²C general call reset
+///
+/// This writes the reset command `0x06` to the general call address `0x00`.
+/// Note that this affects all devices on the bus supporting the general call
+/// and that not all MLX90614/MLX90615 variants respond to it.
+pub fn general_call_reset<E, I2C: I2c<Error = E>>(i2c: &mut I2C) -> Result<(), Error<E>> {
+    i2c.write(0x00, &[GENERAL_CALL_RESET_COMMAND])
+        .map_err(Error::I2C)
+}
+
+const GENERAL_CALL_RESET_COMMAND: u8 = 0x06;
+const CLOCK_STRETCH_CHECK_ATTEMPTS: u8 = 3;
+
 macro_rules! common {
     ($ic_marker:ident, $ic_reg:ident) => {
         impl<E, I2C> Mlx9061x<I2C, ic::$ic_marker>
@@ -41,6 +122,194 @@ macro_rules! common {
             pub fn sleep(&mut self) -> Result<(), Error<E>> {
                 self.write_u8($ic_reg::SLEEP_COMMAND)
             }
+
+            /// Set emissivity epsilon to the typical value of a material
+            ///
+            /// See `set_emissivity()`. Values not supported by the device will
+            /// return `Error::InvalidInputData`.
+            pub fn set_emissivity_material<D: DelayNs>(
+                &mut self,
+                material: Material,
+                delay: &mut D,
+            ) -> Result<(), Error<E>> {
+                self.set_emissivity(material.emissivity(), delay)
+            }
+
+            /// Set emissivity epsilon as a percentage [10-100]
+            ///
+            /// See `set_emissivity()`. Values outside of the range will return
+            /// `Error::InvalidInputData`.
+            pub fn set_emissivity_percent<D: DelayNs>(
+                &mut self,
+                percent: u8,
+                delay: &mut D,
+            ) -> Result<(), Error<E>> {
+                if percent < 10 || percent > 100 {
+                    return Err(Error::InvalidInputData);
+                }
+                self.set_emissivity(f32::from(percent) / 100.0, delay)
+            }
+
+            /// Set emissivity epsilon and verify it, retrying on mismatch
+            ///
+            /// The value is written and read back up to `max_attempts` times until
+            /// it matches. `Error::BadEepromWrite` is returned if all attempts fail.
+            /// Wrong values or 0 attempts will return `Error::InvalidInputData`.
+            pub fn set_emissivity_robust<D: DelayNs>(
+                &mut self,
+                epsilon: f32,
+                delay: &mut D,
+                max_attempts: u8,
+            ) -> Result<(), Error<E>> {
+                let eps = Self::emissivity_to_raw(epsilon)?;
+                if max_attempts == 0 {
+                    return Err(Error::InvalidInputData);
+                }
+                for _ in 0..max_attempts {
+                    let result =
+                        self.write_u16_eeprom_verified($ic_reg::Register::EMISSIVITY, eps, delay);
+                    match self.update_cached_emissivity(eps, result) {
+                        Err(Error::BadEepromWrite) => continue,
+                        result => return result,
+                    }
+                }
+                Err(Error::BadEepromWrite)
+            }
+
+            /// Read the raw emissivity register word as big-endian bytes
+            ///
+            /// This allows verifying the exact value stored by `set_emissivity()`
+            /// without the rounding of the conversion to `f32`.
+            pub fn emissivity_raw_bytes(&mut self) -> Result<[u8; 2], Error<E>> {
+                Ok(self.read_u16($ic_reg::Register::EMISSIVITY)?.to_be_bytes())
+            }
+
+            /// Set emissivity epsilon and verify it with an independent read-back
+            ///
+            /// After writing the value and waiting the configured delay, the
+            /// emissivity is read back in a separate transaction with its own
+            /// PEC check. `Error::BadEepromWrite` is returned if it differs from
+            /// the written value, e.g. for a device which acknowledges writes
+            /// without storing them. Wrong values will return `Error::InvalidInputData`.
+            pub fn set_emissivity_verified_independent<D: DelayNs>(
+                &mut self,
+                epsilon: f32,
+                delay: &mut D,
+            ) -> Result<(), Error<E>> {
+                let eps = Self::emissivity_to_raw(epsilon)?;
+                let result =
+                    self.write_u16_eeprom_verified($ic_reg::Register::EMISSIVITY, eps, delay);
+                self.update_cached_emissivity(eps, result)
+            }
+
+            /// Write the original emissivity again, ignoring errors
+            fn restore_emissivity<D: DelayNs>(&mut self, original: u16, delay: &mut D) {
+                let register = $ic_reg::Register::EMISSIVITY;
+                if self.write_u16_eeprom(register, original, delay).is_ok() {
+                    delay.delay_ms(u32::from(self.eeprom_write_delay_ms));
+                }
+            }
+
+            /// Check whether EEPROM writes work reliably with this I²C master
+            ///
+            /// The current emissivity is written back and verified up to 3 times,
+            /// as the writes fail on masters not supporting clock stretching.
+            /// Returns `true` if an attempt succeeds and `false` if all of them fail.
+            /// After a failure, the original value is written again as a best effort.
+            pub fn check_clock_stretch_support<D: DelayNs>(
+                &mut self,
+                delay: &mut D,
+            ) -> Result<bool, Error<E>> {
+                if self.eeprom_write_protected {
+                    return Err(Error::WriteProtected);
+                }
+                let register = $ic_reg::Register::EMISSIVITY;
+                let original = self.read_u16(register)?;
+                self.emissivity = None;
+                for _ in 0..CLOCK_STRETCH_CHECK_ATTEMPTS {
+                    match self.write_u16_eeprom_verified(register, original, delay) {
+                        Ok(()) => return Ok(true),
+                        Err(Error::BadEepromWrite) => continue,
+                        Err(e) => {
+                            self.restore_emissivity(original, delay);
+                            return Err(e);
+                        }
+                    }
+                }
+                self.restore_emissivity(original, delay);
+                Ok(false)
+            }
+
+            /// Read the device ID and check that it matches the expected one
+            ///
+            /// The ID is always read from the device (see `refresh_device_id()`) so
+            /// that a swapped sensor is detected. `Error::DeviceMismatch` is returned
+            /// if the ID differs.
+            pub fn verify_device_id(&mut self, expected: u64) -> Result<(), Error<E>> {
+                if u64::from(self.refresh_device_id()?) != expected {
+                    return Err(Error::DeviceMismatch);
+                }
+                Ok(())
+            }
+
+            /// Read the junction (die) temperature of the device
+            ///
+            /// The ambient temperature register reports the temperature of the
+            /// sensor die, measured with an on-chip sensor. This returns the same
+            /// value as `ambient_temperature()` and is intended for thermal modeling
+            /// of the device itself. Note that it is not the temperature of the
+            /// surrounding scene, which can differ considerably, e.g. after the
+            /// device warms up due to nearby components.
+            pub fn junction_temperature(&mut self) -> Result<Temperature, Error<E>> {
+                self.ambient_temperature()
+            }
+
+            /// Read the ambient temperature and check it against the operating range
+            ///
+            /// The rated operating range is -40°C to 125°C for the MLX90614 and
+            /// -40°C to 85°C for the MLX90615.
+            /// A reading outside of it usually indicates a faulty or disconnected
+            /// sensor and will return `Error::OutOfRange`.
+            pub fn ambient_temperature_validated(&mut self) -> Result<Temperature, Error<E>> {
+                let t = self.ambient_temperature()?;
+                let mc = t.millicelsius();
+                if mc < $ic_reg::AMBIENT_MIN_MILLICELSIUS || mc > $ic_reg::AMBIENT_MAX_MILLICELSIUS
+                {
+                    return Err(Error::OutOfRange(t));
+                }
+                Ok(t)
+            }
+
+            /// Check whether the ambient temperature reading is plausible
+            ///
+            /// A disconnected sensor on a floating bus may return stuck patterns
+            /// like `0x0000` or `0xFFFF` which can still pass the PEC check.
+            /// This reads the raw ambient temperature and returns `false` for such
+            /// patterns or for values outside of the rated operating range
+            /// (see `ambient_temperature_validated()`).
+            pub fn is_connected(&mut self) -> Result<bool, Error<E>> {
+                let raw = self.read_u16($ic_reg::Register::TA)?;
+                if raw == 0 || raw == 0xFFFF {
+                    return Ok(false);
+                }
+                let mc = Temperature::from_raw(raw).millicelsius();
+                Ok(mc >= $ic_reg::AMBIENT_MIN_MILLICELSIUS
+                    && mc <= $ic_reg::AMBIENT_MAX_MILLICELSIUS)
+            }
+
+            /// Bring the communication back to a known state after an aborted transfer
+            ///
+            /// If a transfer (e.g. an EEPROM write) was interrupted, the device may
+            /// still be busy or waiting for the rest of the transaction.
+            /// This waits the configured EEPROM write delay so that any pending
+            /// EEPROM operation can complete and then issues a complete dummy
+            /// read of the ambient temperature RAM register, which terminates any
+            /// pending transaction and re-synchronizes the SMBus state machine.
+            /// The data read is discarded without checking the PEC.
+            pub fn abort_and_resync<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+                delay.delay_ms(u32::from(self.eeprom_write_delay_ms));
+                self.read_u16_unverified($ic_reg::Register::TA).map(|_| ())
+            }
         }
     };
 }
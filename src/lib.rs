@@ -55,6 +55,36 @@
 //! mlx9061x = { version = "0.3.0", features = ["defmt"] }
 //! ```
 //!
+//! ### async
+//!
+//! To enable the asynchronous functions in the `asynch` module using
+//! [`embedded-hal-async`](https://crates.io/crates/embedded-hal-async), add the feature "`async`".
+//!
+//! ```toml
+//! [dependencies]
+//! mlx9061x = { version = "0.3.0", features = ["async"] }
+//! ```
+//!
+//! ### bitflags
+//!
+//! To read the MLX90614 device flags as a [`bitflags`](https://crates.io/crates/bitflags)
+//! type with `flag_bits()`, add the feature "`bitflags`".
+//!
+//! ```toml
+//! [dependencies]
+//! mlx9061x = { version = "0.3.0", features = ["bitflags"] }
+//! ```
+//!
+//! ### embedded-io
+//!
+//! To stream the MLX90614 raw IR data to an [`embedded-io`](https://crates.io/crates/embedded-io)
+//! writer with `stream_raw_ir()`, add the feature "`embedded-io`".
+//!
+//! ```toml
+//! [dependencies]
+//! mlx9061x = { version = "0.3.0", features = ["embedded-io"] }
+//! ```
+//!
 //! ## Usage examples (see also examples folder)
 //!
 //! To use this driver, import this crate and an `embedded_hal` implementation,
@@ -221,14 +251,21 @@
 
 use core::marker::PhantomData;
 
+#[cfg(feature = "async")]
+pub mod asynch;
 /// MLX90614 Specifics
 pub mod mlx90614;
 /// MLX90615 Specifics
 pub mod mlx90615;
 
 mod types;
-pub use crate::types::{ic, Error, SlaveAddr, Temperature};
+pub use crate::types::{
+    ic, Celsius, Error, Fahrenheit, Kelvin, Material, SlaveAddr, Temperature, TemperatureUnit,
+    Verified,
+};
 mod common;
+pub use crate::common::{general_call_reset, scan};
+pub use crate::register_access::crc8;
 mod register_access;
 
 /// MLX90614/MLX90615 device driver
@@ -238,5 +275,42 @@ pub struct Mlx9061x<I2C, IC> {
     i2c: I2C,
     eeprom_write_delay_ms: u8,
     address: u8,
+    sample_index: u32,
+    device_id: Option<u64>,
+    last_object1: Option<Temperature>,
+    peak_object1: Option<Temperature>,
+    thermostat_on: bool,
+    settling_time_ms: u32,
+    eeprom_write_protected: bool,
+    ta_range: Option<u16>,
+    emissivity: Option<u16>,
+    /// Emissivity independent raw IR scale in K⁴ per count
+    ir_scale: Option<f32>,
+    /// Slope and offset of the software calibration
+    calibration: (f32, f32),
+    filter_window: u8,
+    filter_samples: heapless::Deque<u16, MAX_FILTER_WINDOW>,
+    ema_object1: Option<f32>,
     _ic: PhantomData<IC>,
 }
+
+/// Maximum window size of the rolling median filter
+const MAX_FILTER_WINDOW: usize = 15;
+/// Default window size of the rolling median filter
+const DEFAULT_FILTER_WINDOW: u8 = 5;
+
+/// Maximum SMBus clock frequency supported by the devices in Hz
+pub const MAX_SMBUS_FREQ_HZ: u32 = 100_000;
+
+/// SMBus timeout in milliseconds
+const SMBUS_TIMEOUT_MS: u32 = 35;
+
+/// Recommended I²C peripheral timeout in milliseconds
+///
+/// The devices reset their SMBus interface if the clock is held low for
+/// longer than the SMBus timeout of 35ms, aborting any ongoing transfer.
+/// An I²C peripheral timeout of at least this value avoids reporting errors
+/// for transfers the device would still complete.
+pub const fn recommended_i2c_timeout_ms() -> u32 {
+    SMBUS_TIMEOUT_MS
+}
@@ -2,11 +2,16 @@
 
 use crate::{
     ic,
-    register_access::mlx90614::{self, Register, DEV_ADDR},
-    Error, Mlx9061x, SlaveAddr, Temperature,
+    register_access::{
+        crc8,
+        mlx90614::{self, Register, DEV_ADDR},
+    },
+    Error, Mlx9061x, SlaveAddr, Temperature, TemperatureUnit, Verified, DEFAULT_FILTER_WINDOW,
+    MAX_FILTER_WINDOW,
 };
 use core::marker::PhantomData;
 use embedded_hal::{delay::DelayNs, digital::OutputPin, i2c::I2c};
+use heapless::Vec;
 
 impl<E, I2C> Mlx9061x<I2C, ic::Mlx90614>
 where
@@ -31,6 +36,20 @@ where
             i2c,
             eeprom_write_delay_ms,
             address,
+            sample_index: 0,
+            device_id: None,
+            last_object1: None,
+            peak_object1: None,
+            thermostat_on: false,
+            settling_time_ms: 0,
+            eeprom_write_protected: false,
+            ta_range: None,
+            emissivity: None,
+            ir_scale: None,
+            calibration: (1.0, 0.0),
+            filter_window: DEFAULT_FILTER_WINDOW,
+            filter_samples: heapless::Deque::new(),
+            ema_object1: None,
             _ic: PhantomData,
         })
     }
@@ -40,11 +59,172 @@ where
         Self::convert_to_temp(self.read_u16(Register::TA)?)
     }
 
+    /// Read the ambient temperature clamped to the configured TARANGE
+    ///
+    /// The TARANGE EEPROM register holds the ambient temperature range used
+    /// for the PWM output: Ta min in the low byte and Ta max in the high byte,
+    /// each encoded as `byte = (Ta[°C] + 38.2) * 64 / 100`.
+    /// The register is only read on the first call and cached afterwards
+    /// (see `reset_cache()`). The ambient temperature reading is clamped to this range.
+    pub fn ambient_temperature_ranged(&mut self) -> Result<Temperature, Error<E>> {
+        let t = self.ambient_temperature()?;
+        let range = match self.ta_range {
+            Some(range) => range,
+            None => {
+                let range = self.read_u16(Register::TARANGE)?;
+                self.ta_range = Some(range);
+                range
+            }
+        };
+        let to_temp = |byte: u16| Temperature::from_celsius(f32::from(byte) * 100.0 / 64.0 - 38.2);
+        let min = to_temp(range & 0xFF);
+        let max = to_temp(range >> 8);
+        if t.raw() < min.raw() {
+            Ok(min)
+        } else if t.raw() > max.raw() {
+            Ok(max)
+        } else {
+            Ok(t)
+        }
+    }
+
     /// Read the object 1 temperature
     pub fn object1_temperature(&mut self) -> Result<Temperature, Error<E>> {
         Self::convert_to_temp(self.read_u16(Register::TOBJ1)?)
     }
 
+    /// Read the object 1 temperature together with a confidence indicator
+    ///
+    /// The confidence is derived from the error flag of the reading and how
+    /// close the channel 1 raw IR data is to saturation:
+    /// - `Confidence::Low`: the error flag is set or the raw IR magnitude is
+    ///   at least 95% of the full scale. For flagged readings the temperature
+    ///   without the flag is returned.
+    /// - `Confidence::Medium`: the raw IR magnitude is at least 75% of the full scale.
+    /// - `Confidence::High`: otherwise.
+    pub fn object1_with_confidence(&mut self) -> Result<(Temperature, Confidence), Error<E>> {
+        let (t, flagged) = match self.object1_temperature() {
+            Ok(t) => (t, false),
+            Err(Error::BadRead(t)) => (t, true),
+            Err(e) => return Err(e),
+        };
+        let ir = u32::from(self.raw_ir_channel1()?.unsigned_abs());
+        let full_scale = u32::from(i16::MAX as u16);
+        let confidence = if flagged || ir * 100 >= full_scale * 95 {
+            Confidence::Low
+        } else if ir * 100 >= full_scale * 75 {
+            Confidence::Medium
+        } else {
+            Confidence::High
+        };
+        Ok((t, confidence))
+    }
+
+    /// Read the object 1 temperature classified into valid, flagged or saturated
+    ///
+    /// This reads the object 1 temperature and the channel 1 raw IR data.
+    /// If the raw IR magnitude is at the full scale, `Reading::Saturated` is returned.
+    /// Otherwise, if the error flag is set, `Reading::Flagged` is returned with
+    /// the temperature without the flag.
+    pub fn object1_reading(&mut self) -> Result<Reading, Error<E>> {
+        let reading = match self.object1_temperature() {
+            Ok(t) => Reading::Valid(t),
+            Err(Error::BadRead(t)) => Reading::Flagged(t),
+            Err(e) => return Err(e),
+        };
+        if self.raw_ir_channel1()?.unsigned_abs() >= i16::MAX as u16 {
+            return Ok(Reading::Saturated);
+        }
+        Ok(reading)
+    }
+
+    /// Read the object 1 temperature in celsius with the software calibration applied
+    ///
+    /// The reading is corrected as `slope * t + offset`. Without calibration the
+    /// slope is 1 and the offset 0. See `calibrate_two_point()`.
+    pub fn object1_celsius(&mut self) -> Result<f32, Error<E>> {
+        let t = self.object1_temperature()?.celsius();
+        let (slope, offset) = self.calibration;
+        Ok(slope * t + offset)
+    }
+
+    /// Read the object 1 temperature in celsius corrected with a lookup table
+    ///
+    /// `lut` contains `(measured, true)` temperature pairs in celsius, which
+    /// must be sorted by strictly increasing measured temperature.
+    /// The reading is corrected by piecewise-linear interpolation between the
+    /// surrounding pairs. Readings outside of the table are extrapolated from
+    /// the first or last two pairs.
+    ///
+    /// Less than 2 pairs or an unsorted table will return `Error::InvalidInputData`.
+    pub fn object1_linearized(&mut self, lut: &[(f32, f32)]) -> Result<f32, Error<E>> {
+        if lut.len() < 2 || lut.windows(2).any(|w| w[0].0 >= w[1].0) {
+            return Err(Error::InvalidInputData);
+        }
+        let t = self.object1_temperature()?.celsius();
+        let segment = lut
+            .windows(2)
+            .find(|w| t <= w[1].0)
+            .unwrap_or(&lut[lut.len() - 2..]);
+        let ((x0, y0), (x1, y1)) = (segment[0], segment[1]);
+        Ok(y0 + (t - x0) * (y1 - y0) / (x1 - x0))
+    }
+
+    /// Compute the software calibration from two reference points
+    ///
+    /// `measured_low` and `measured_high` are the object 1 temperatures in celsius
+    /// reported for targets with the known temperatures `ref_low` and `ref_high`.
+    /// The slope and offset mapping the measured values onto the reference
+    /// values are stored and used by `object1_celsius()`.
+    ///
+    /// Equal reference or measured temperatures will return `Error::InvalidInputData`.
+    pub fn calibrate_two_point(
+        &mut self,
+        ref_low: f32,
+        measured_low: f32,
+        ref_high: f32,
+        measured_high: f32,
+    ) -> Result<(), Error<E>> {
+        if ref_low == ref_high || measured_low == measured_high {
+            return Err(Error::InvalidInputData);
+        }
+        let slope = (ref_high - ref_low) / (measured_high - measured_low);
+        self.calibration = (slope, ref_low - slope * measured_low);
+        Ok(())
+    }
+
+    /// Read the object 1 temperature in celsius returning NaN for flagged readings
+    ///
+    /// If the device reports an error (bit 15 set), `f32::NAN` is returned
+    /// instead of `Error::BadRead`. Bus and PEC errors are still returned as errors.
+    pub fn object1_celsius_or_nan(&mut self) -> Result<f32, Error<E>> {
+        match self.object1_temperature() {
+            Ok(t) => Ok(t.celsius()),
+            Err(Error::BadRead(_)) => Ok(f32::NAN),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Read the object 1 temperature in the unit given as type parameter
+    ///
+    /// For example: `sensor.read_object1::<Celsius>()`.
+    pub fn read_object1<U: TemperatureUnit>(&mut self) -> Result<U, Error<E>> {
+        self.object1_temperature().map(U::from_temperature)
+    }
+
+    /// Read the object 1 temperature with the PEC verification encoded in the type
+    pub fn object1_temperature_verified(&mut self) -> Result<Verified<Temperature>, Error<E>> {
+        self.object1_temperature().map(Verified)
+    }
+
+    /// Read the object 1 temperature without verifying the PEC
+    ///
+    /// Corrupted readings will not be detected. Prefer `object1_temperature()`
+    /// or `object1_temperature_verified()` where possible.
+    pub fn object1_temperature_unverified(&mut self) -> Result<Temperature, Error<E>> {
+        Self::convert_to_temp(self.read_u16_unverified(Register::TOBJ1)?)
+    }
+
     /// Read the object 2 temperature
     ///
     /// Note that this is only available in dual-zone thermopile device variants.
@@ -52,6 +232,377 @@ where
         Self::convert_to_temp(self.read_u16(Register::TOBJ2)?)
     }
 
+    /// Read the object 2 temperature if the device has a dual IR sensor
+    ///
+    /// The configuration register 1 is read first and `None` is returned
+    /// for single IR sensor configurations without reading the object 2 temperature.
+    pub fn object2_temperature_opt(&mut self) -> Result<Option<Temperature>, Error<E>> {
+        if self.config_1()?.dual_ir_sensor {
+            self.object2_temperature().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Read both object temperatures and return their average
+    ///
+    /// This is useful when both thermopiles view the same target for redundancy.
+    /// A channel whose reading is flagged as erroneous is skipped and the other
+    /// one is returned. If both readings are flagged, `Error::BadRead` is
+    /// returned with the object 1 temperature.
+    ///
+    /// Note that this is only available in dual-zone thermopile device variants.
+    pub fn object_average(&mut self) -> Result<Temperature, Error<E>> {
+        let object1 = Self::split_flagged(self.object1_temperature())?;
+        let object2 = Self::split_flagged(self.object2_temperature())?;
+        match (object1, object2) {
+            (Ok(t1), Ok(t2)) => Ok(Temperature(
+                ((u32::from(t1.raw()) + u32::from(t2.raw())) / 2) as u16,
+            )),
+            (Ok(t), Err(_)) | (Err(_), Ok(t)) => Ok(t),
+            (Err(t), Err(_)) => Err(Error::BadRead(t)),
+        }
+    }
+
+    /// Read the object 1 temperature together with a sample index
+    ///
+    /// The index starts at 0 and is incremented with each successful reading.
+    /// It wraps around on overflow.
+    pub fn object1_indexed(&mut self) -> Result<(u32, Temperature), Error<E>> {
+        let t = self.object1_temperature()?;
+        let index = self.sample_index;
+        self.sample_index = self.sample_index.wrapping_add(1);
+        Ok((index, t))
+    }
+
+    /// Read the object 1 temperature only if it changed since the last returned one
+    ///
+    /// Returns `None` if the reading is the same as the last one returned by this method.
+    /// The first call always returns the reading.
+    pub fn object1_if_changed(&mut self) -> Result<Option<Temperature>, Error<E>> {
+        let t = self.object1_temperature()?;
+        if self.last_object1 == Some(t) {
+            return Ok(None);
+        }
+        self.last_object1 = Some(t);
+        Ok(Some(t))
+    }
+
+    /// Read the object 1 temperature twice and check that both readings agree
+    ///
+    /// The second reading is returned if it is within `tolerance_celsius` of the first one.
+    /// Otherwise `Error::Unstable` is returned.
+    /// A negative tolerance will return `Error::InvalidInputData`.
+    pub fn object1_temperature_debounced(
+        &mut self,
+        tolerance_celsius: f32,
+    ) -> Result<Temperature, Error<E>> {
+        if tolerance_celsius < 0.0 {
+            return Err(Error::InvalidInputData);
+        }
+        let first = self.object1_temperature()?;
+        let second = self.object1_temperature()?;
+        if difference_celsius(first, second) > tolerance_celsius {
+            return Err(Error::Unstable);
+        }
+        Ok(second)
+    }
+
+    /// Read the object 1 temperature in celsius through an exponential moving average
+    ///
+    /// Each call reads the object 1 temperature once and updates the average
+    /// stored in the driver as `ema = alpha * t + (1 - alpha) * ema`, which is returned.
+    /// The first reading initializes the average. See `reset_cache()`.
+    /// An `alpha` outside of [0.0-1.0] will return `Error::InvalidInputData`.
+    pub fn object1_smoothed(&mut self, alpha: f32) -> Result<f32, Error<E>> {
+        if !(0.0..=1.0).contains(&alpha) {
+            return Err(Error::InvalidInputData);
+        }
+        let t = self.object1_temperature()?.celsius();
+        let ema = match self.ema_object1 {
+            Some(ema) => alpha * t + (1.0 - alpha) * ema,
+            None => t,
+        };
+        self.ema_object1 = Some(ema);
+        Ok(ema)
+    }
+
+    /// Read the object 1 temperature and return the highest one seen
+    ///
+    /// Each call reads the object 1 temperature once and returns the maximum
+    /// of all readings since the driver was created or `reset_peak()` or
+    /// `reset_cache()` was called.
+    pub fn object1_peak_hold(&mut self) -> Result<Temperature, Error<E>> {
+        let t = self.object1_temperature()?;
+        let peak = match self.peak_object1 {
+            Some(peak) if peak.raw() >= t.raw() => peak,
+            _ => t,
+        };
+        self.peak_object1 = Some(peak);
+        Ok(peak)
+    }
+
+    /// Read the object 1 temperature and return a thermostat state with hysteresis
+    ///
+    /// The state turns on when the reading is above `on_above` and turns off
+    /// when it is below `off_below`. In between, the previous state is held.
+    /// The state is stored in the driver and is initially off.
+    ///
+    /// `off_below` must not be higher than `on_above`. Wrong values will
+    /// return `Error::InvalidInputData`.
+    pub fn thermostat_object1(
+        &mut self,
+        on_above: Temperature,
+        off_below: Temperature,
+    ) -> Result<bool, Error<E>> {
+        if off_below.raw() > on_above.raw() {
+            return Err(Error::InvalidInputData);
+        }
+        let t = self.object1_temperature()?;
+        if t.raw() > on_above.raw() {
+            self.thermostat_on = true;
+        } else if t.raw() < off_below.raw() {
+            self.thermostat_on = false;
+        }
+        Ok(self.thermostat_on)
+    }
+
+    /// Clear the peak held by `object1_peak_hold()`
+    pub fn reset_peak(&mut self) {
+        self.peak_object1 = None;
+    }
+
+    /// Read the object 1 temperature several times and return the median
+    ///
+    /// The median is more robust to single glitches than the mean.
+    /// The number of samples must be odd and at most 15. Otherwise
+    /// `Error::InvalidInputData` is returned.
+    pub fn object1_median(&mut self, samples: u8) -> Result<Temperature, Error<E>> {
+        if samples & 1 == 0 || usize::from(samples) > MAX_FILTER_WINDOW {
+            return Err(Error::InvalidInputData);
+        }
+        let mut raw = [0; MAX_FILTER_WINDOW];
+        let raw = &mut raw[..usize::from(samples)];
+        for value in raw.iter_mut() {
+            *value = self.object1_temperature()?.raw();
+        }
+        raw.sort_unstable();
+        Ok(Temperature(raw[raw.len() / 2]))
+    }
+
+    /// Set the window size of the rolling median filter used by `object1_filtered()`
+    ///
+    /// The window must contain between 1 and 15 samples. The default is 5.
+    /// Changing the window clears the collected samples.
+    /// Wrong values will return `Error::InvalidInputData`.
+    pub fn set_filter_window(&mut self, window: u8) -> Result<(), Error<E>> {
+        if window == 0 || usize::from(window) > MAX_FILTER_WINDOW {
+            return Err(Error::InvalidInputData);
+        }
+        self.filter_window = window;
+        self.filter_samples.clear();
+        Ok(())
+    }
+
+    /// Read the object 1 temperature through a rolling median filter
+    ///
+    /// Each call reads the object 1 temperature once, adds it to the filter
+    /// window replacing the oldest sample if the window is full and returns
+    /// the median of the samples in the window.
+    /// For an even number of samples the lower of the two middle samples is returned.
+    /// See `set_filter_window()`.
+    pub fn object1_filtered(&mut self) -> Result<Temperature, Error<E>> {
+        let t = self.object1_temperature()?;
+        if self.filter_samples.len() >= usize::from(self.filter_window) {
+            self.filter_samples.pop_front();
+        }
+        // cannot fail as the window is at most MAX_FILTER_WINDOW
+        let _ = self.filter_samples.push_back(t.raw());
+        let mut sorted = [0; MAX_FILTER_WINDOW];
+        let sorted = &mut sorted[..self.filter_samples.len()];
+        for (value, sample) in sorted.iter_mut().zip(self.filter_samples.iter()) {
+            *value = *sample;
+        }
+        sorted.sort_unstable();
+        Ok(Temperature(sorted[(sorted.len() - 1) / 2]))
+    }
+
+    /// Read the ambient and object 1 temperatures together with a timestamp
+    ///
+    /// The timestamp is taken from `clock` right before reading the ambient
+    /// and then the object 1 temperature.
+    /// The result is `(timestamp, ambient, object1)`.
+    pub fn read_timestamped<C: FnMut() -> u64>(
+        &mut self,
+        clock: &mut C,
+    ) -> Result<(u64, Temperature, Temperature), Error<E>> {
+        let timestamp = clock();
+        let ambient = self.ambient_temperature()?;
+        let object1 = self.object1_temperature()?;
+        Ok((timestamp, ambient, object1))
+    }
+
+    /// Read the raw ambient and object 1 temperature words into a buffer
+    ///
+    /// `out[0]` is set to the raw ambient temperature and `out[1]` to the raw
+    /// object 1 temperature. The PEC is verified but the values are not
+    /// otherwise interpreted, so the error flag (bit 15) is included.
+    pub fn read_pair_into(&mut self, out: &mut [u16; 2]) -> Result<(), Error<E>> {
+        out[0] = self.read_u16(Register::TA)?;
+        out[1] = self.read_u16(Register::TOBJ1)?;
+        Ok(())
+    }
+
+    /// Read the raw object 1 temperature word as big-endian bytes
+    ///
+    /// The PEC is verified but the value is not otherwise interpreted,
+    /// so the error flag (bit 15) is included.
+    pub fn object1_raw_bytes(&mut self) -> Result<[u8; 2], Error<E>> {
+        Ok(self.read_u16(Register::TOBJ1)?.to_be_bytes())
+    }
+
+    /// Poll the object 1 temperature until two consecutive readings agree
+    ///
+    /// This is useful after changing the emissivity or the configuration.
+    /// The configuration register 1 is read first to wait one measurement refresh
+    /// period between readings. Returns the last reading once it is within
+    /// `tolerance_celsius` of the previous one, or `Error::Unstable` if this does
+    /// not happen within `max_samples` readings.
+    /// A negative tolerance or less than 2 samples will return `Error::InvalidInputData`.
+    pub fn wait_for_stable_object1<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        tolerance_celsius: f32,
+        max_samples: u8,
+    ) -> Result<Temperature, Error<E>> {
+        if tolerance_celsius < 0.0 || max_samples < 2 {
+            return Err(Error::InvalidInputData);
+        }
+        let config = self.config_1()?;
+        let period_us = config.refresh_period_us();
+        let mut previous = self.object1_temperature()?;
+        for _ in 1..max_samples {
+            delay.delay_us(period_us);
+            let t = self.object1_temperature()?;
+            if difference_celsius(t, previous) <= tolerance_celsius {
+                return Ok(t);
+            }
+            previous = t;
+        }
+        Err(Error::Unstable)
+    }
+
+    /// Estimate the ambient temperature trend in degrees Celsius per sample
+    ///
+    /// This takes `samples` ambient temperature readings one refresh period
+    /// apart (depending on the FIR and IR sensor configuration) and returns the
+    /// least-squares slope. The sums are computed with integer math on the raw values.
+    /// Less than 2 samples will return `Error::InvalidInputData`.
+    pub fn ambient_trend<D: DelayNs>(
+        &mut self,
+        samples: u8,
+        delay: &mut D,
+    ) -> Result<f32, Error<E>> {
+        if samples < 2 {
+            return Err(Error::InvalidInputData);
+        }
+        let config = self.config_1()?;
+        let period_us = config.refresh_period_us();
+        let n = i64::from(samples);
+        let (mut sum_x, mut sum_y, mut sum_xy, mut sum_xx) = (0i64, 0i64, 0i64, 0i64);
+        for x in 0..n {
+            if x != 0 {
+                delay.delay_us(period_us);
+            }
+            let y = i64::from(self.ambient_temperature()?.raw());
+            sum_x += x;
+            sum_y += y;
+            sum_xy += x * y;
+            sum_xx += x * x;
+        }
+        let numerator = n * sum_xy - sum_x * sum_y;
+        let denominator = n * sum_xx - sum_x * sum_x;
+        // raw values are in 0.02K units
+        Ok(numerator as f32 / denominator as f32 * 0.02)
+    }
+
+    /// Poll the object 1 temperature at a rate adapted to its changes
+    ///
+    /// The object 1 temperature is read and passed to `f` repeatedly until `f`
+    /// returns `false`. After a reading which differs from the previous one by
+    /// more than `change_threshold` degrees, the next reading is taken after
+    /// `fast_ms` milliseconds. Otherwise, it is taken after `slow_ms` milliseconds.
+    pub fn adaptive_sample<F, D>(
+        &mut self,
+        delay: &mut D,
+        slow_ms: u32,
+        fast_ms: u32,
+        change_threshold: f32,
+        mut f: F,
+    ) -> Result<(), Error<E>>
+    where
+        F: FnMut(Temperature) -> bool,
+        D: DelayNs,
+    {
+        let mut previous: Option<Temperature> = None;
+        loop {
+            let t = self.object1_temperature()?;
+            if !f(t) {
+                return Ok(());
+            }
+            let changed = match previous {
+                Some(p) => difference_celsius(p, t) > change_threshold,
+                None => false,
+            };
+            previous = Some(t);
+            delay.delay_ms(if changed { fast_ms } else { slow_ms });
+        }
+    }
+
+    /// Wait until the object 1 temperature rises above a threshold
+    ///
+    /// The object 1 temperature is read every `interval_ms` milliseconds and
+    /// the first reading above `threshold` is returned.
+    /// `Error::Timeout` is returned if no reading exceeded the threshold
+    /// within `timeout_ms` milliseconds.
+    /// An interval of 0 will return `Error::InvalidInputData`.
+    pub fn wait_until_object1_above<D: DelayNs>(
+        &mut self,
+        threshold: Temperature,
+        delay: &mut D,
+        interval_ms: u32,
+        timeout_ms: u32,
+    ) -> Result<Temperature, Error<E>> {
+        if interval_ms == 0 {
+            return Err(Error::InvalidInputData);
+        }
+        let mut elapsed_ms: u32 = 0;
+        loop {
+            let t = self.object1_temperature()?;
+            if t.raw() > threshold.raw() {
+                return Ok(t);
+            }
+            elapsed_ms = elapsed_ms.saturating_add(interval_ms);
+            if elapsed_ms > timeout_ms {
+                return Err(Error::Timeout);
+            }
+            delay.delay_ms(interval_ms);
+        }
+    }
+
+    /// Separate flagged readings from other errors
+    ///
+    /// Flagged readings are returned as `Ok(Err(temperature))`.
+    fn split_flagged(
+        result: Result<Temperature, Error<E>>,
+    ) -> Result<Result<Temperature, Temperature>, Error<E>> {
+        match result {
+            Ok(t) => Ok(Ok(t)),
+            Err(Error::BadRead(t)) => Ok(Err(t)),
+            Err(e) => Err(e),
+        }
+    }
+
     fn convert_to_temp(raw: u16) -> Result<Temperature, Error<E>> {
         if raw & 0x8000 != 0 {
             return Err(Error::BadRead(Temperature(raw & 0x7FFF)));
@@ -60,68 +611,1009 @@ where
     }
 
     /// Read the channel 1 raw IR data
+    ///
+    /// The raw IR data is stored in sign-magnitude format, bit 15 being the sign.
     pub fn raw_ir_channel1(&mut self) -> Result<i16, Error<E>> {
         self.read_i16(Register::RAW_IR1)
     }
 
-    /// Read the channel 2 raw IR data
-    pub fn raw_ir_channel2(&mut self) -> Result<i16, Error<E>> {
-        self.read_i16(Register::RAW_IR2)
+    /// Read the channel 2 raw IR data
+    ///
+    /// The raw IR data is stored in sign-magnitude format, bit 15 being the sign.
+    pub fn raw_ir_channel2(&mut self) -> Result<i16, Error<E>> {
+        self.read_i16(Register::RAW_IR2)
+    }
+
+    /// Estimate the object temperature in degrees Celsius with a known ambient temperature
+    ///
+    /// This reads the channel 1 raw IR data and applies the model of
+    /// `fast_object_estimate()` using `ambient_celsius` instead of the ambient
+    /// temperature measured by the device and the emissivity `epsilon` instead
+    /// of the one stored in the EEPROM:
+    ///
+    /// `T^4 = Ta^4 + k * RAW_IR1 / epsilon` (in Kelvin)
+    ///
+    /// This is useful when the device is thermally isolated and the real ambient
+    /// temperature is known from another sensor.
+    /// The raw IR scale `k` is derived on the first call. See `ir_scale()`.
+    /// An emissivity outside of (0.0-1.0], an ambient temperature below 0K
+    /// or an estimate below 0K will return `Error::InvalidInputData`.
+    pub fn object1_with_ambient_override(
+        &mut self,
+        ambient_celsius: f32,
+        epsilon: f32,
+    ) -> Result<f32, Error<E>> {
+        if epsilon <= 0.0 || epsilon > 1.0 {
+            return Err(Error::InvalidInputData);
+        }
+        let scale = self.ir_scale()?;
+        let ir = self.raw_ir_channel1()?;
+        let object = ir_object_kelvin(ambient_celsius + 273.15, ir, scale, epsilon)
+            .ok_or(Error::InvalidInputData)?;
+        Ok(object - 273.15)
+    }
+
+    /// Read the channel 1 raw IR data as a percentage of the full scale
+    ///
+    /// The signed range -32767 to 32767 of the raw IR data is linearly mapped
+    /// to 0% to 100%, so a count of 0 corresponds to 50%.
+    pub fn raw_ir_channel1_percent(&mut self) -> Result<f32, Error<E>> {
+        let ir = f32::from(self.raw_ir_channel1()?);
+        Ok((ir + 32767.0) / 65534.0 * 100.0)
+    }
+
+    /// Read the difference between the channel 1 and channel 2 raw IR data
+    ///
+    /// This returns `RAW_IR1 - RAW_IR2`, which is meaningful for dual-zone
+    /// thermopile devices, e.g. for gradient sensing.
+    pub fn raw_ir_difference(&mut self) -> Result<i32, Error<E>> {
+        let ir1 = self.raw_ir_channel1()?;
+        let ir2 = self.raw_ir_channel2()?;
+        Ok(i32::from(ir1) - i32::from(ir2))
+    }
+
+    /// Read the ratio between the channel 1 and channel 2 raw IR data
+    ///
+    /// This returns `RAW_IR1 / RAW_IR2`.
+    /// A channel 2 value of 0 will return `Error::InvalidInputData`.
+    pub fn raw_ir_ratio(&mut self) -> Result<f32, Error<E>> {
+        let ir1 = self.raw_ir_channel1()?;
+        let ir2 = self.raw_ir_channel2()?;
+        if ir2 == 0 {
+            return Err(Error::InvalidInputData);
+        }
+        Ok(f32::from(ir1) / f32::from(ir2))
+    }
+
+    /// Get the raw IR scale of this device in K⁴ per count
+    ///
+    /// The channel 1 raw IR data is proportional to the difference of the
+    /// fourth powers of the object and ambient temperatures and to the emissivity.
+    /// The proportionality depends on the gain and the sensitivity of each
+    /// device, so it is derived from readings taken together:
+    ///
+    /// `k = epsilon * (Tobj1^4 - Ta^4) / RAW_IR1` (in Kelvin)
+    ///
+    /// The scale is derived on the first call and cached afterwards.
+    /// Use `refresh_ir_scale()` to derive it again.
+    pub fn ir_scale(&mut self) -> Result<f32, Error<E>> {
+        match self.ir_scale {
+            Some(scale) => Ok(scale),
+            None => self.refresh_ir_scale(),
+        }
+    }
+
+    /// Derive the raw IR scale from the device and update the cached value
+    ///
+    /// This reads the object 1 temperature, the ambient temperature, the
+    /// channel 1 raw IR data and the emissivity.
+    /// The object 1 temperature is filtered by the device, so the target
+    /// should be steady and at a different temperature than the device.
+    /// A raw IR value of 0, a scale which is not positive or an emissivity
+    /// of 0 stored in the EEPROM will return `Error::InvalidInputData`.
+    /// See `ir_scale()`.
+    pub fn refresh_ir_scale(&mut self) -> Result<f32, Error<E>> {
+        let object = self.object1_temperature()?.kelvin();
+        let ambient = self.ambient_temperature()?.kelvin();
+        let ir = self.raw_ir_channel1()?;
+        let epsilon = self.cached_emissivity()?;
+        let scale = derive_ir_scale(object, ambient, ir, epsilon).ok_or(Error::InvalidInputData)?;
+        self.ir_scale = Some(scale);
+        Ok(scale)
+    }
+
+    /// Read the ambient compensation residual in degrees
+    ///
+    /// This reads the object 1 temperature, the ambient temperature and the
+    /// channel 1 raw IR data and returns the difference between the object 1
+    /// temperature and the estimate of `fast_object_estimate()` from the same
+    /// readings:
+    ///
+    /// `residual = Tobj1 - (Ta^4 + k * RAW_IR1 / epsilon)^(1/4)` (in Kelvin)
+    ///
+    /// The estimate uses a fixed raw IR scale `k` while the device compensates
+    /// its sensitivity for the ambient temperature, so the residual shows
+    /// how much this compensation changed since the scale was derived.
+    /// If the scale has not been derived yet, it is derived from these
+    /// readings and the residual is 0. See `ir_scale()`.
+    /// This is only meant for research purposes.
+    pub fn compensation_residual(&mut self) -> Result<f32, Error<E>> {
+        let object = self.object1_temperature()?.kelvin();
+        let ambient = self.ambient_temperature()?.kelvin();
+        let ir = self.raw_ir_channel1()?;
+        let epsilon = self.cached_emissivity()?;
+        let scale = match self.ir_scale {
+            Some(scale) => scale,
+            None => {
+                let scale =
+                    derive_ir_scale(object, ambient, ir, epsilon).ok_or(Error::InvalidInputData)?;
+                self.ir_scale = Some(scale);
+                scale
+            }
+        };
+        let estimate =
+            ir_object_kelvin(ambient, ir, scale, epsilon).ok_or(Error::InvalidInputData)?;
+        Ok(object - estimate)
+    }
+
+    /// Estimate the object temperature in degrees Celsius from the raw IR data
+    ///
+    /// This reads the channel 1 raw IR data and the ambient temperature and
+    /// computes an unfiltered estimate with the Stefan-Boltzmann law, using
+    /// the raw IR scale `k` of the device and the emissivity stored in the EEPROM:
+    ///
+    /// `T^4 = Ta^4 + k * RAW_IR1 / epsilon` (in Kelvin)
+    ///
+    /// The scale and the emissivity are read on the first call and cached
+    /// afterwards. See `ir_scale()`. The cached emissivity is updated when it is
+    /// written through the driver and can be read again with `refresh_emissivity()`.
+    /// The estimate does not suffer from the delay of the IIR filter but does
+    /// not include the ambient compensation of the device, so it is less accurate
+    /// than `object1_temperature()`. Only use it where latency matters more
+    /// than accuracy, e.g. for triggering.
+    /// An estimate below 0K will return `Error::InvalidInputData`.
+    pub fn fast_object_estimate(&mut self) -> Result<f32, Error<E>> {
+        let scale = self.ir_scale()?;
+        let epsilon = self.cached_emissivity()?;
+        let ir = self.raw_ir_channel1()?;
+        let ambient = self.ambient_temperature()?.kelvin();
+        let object =
+            ir_object_kelvin(ambient, ir, scale, epsilon).ok_or(Error::InvalidInputData)?;
+        Ok(object - 273.15)
+    }
+
+    /// Read the object 1 temperature in celsius as if another emissivity was configured
+    ///
+    /// This reads the object 1 and ambient temperatures and the emissivity
+    /// stored in the EEPROM and rescales the reading to `assumed_eps` without
+    /// writing to the EEPROM. The object radiation is inversely proportional
+    /// to the emissivity, so the rescaled temperature is:
+    ///
+    /// `T^4 = Ta^4 + (Tobj1^4 - Ta^4) * epsilon / assumed_eps` (in Kelvin)
+    ///
+    /// This allows previewing the effect of an emissivity change.
+    /// An assumed emissivity outside of (0.0-1.0], an emissivity of 0 stored
+    /// in the EEPROM or a rescaled value below 0K will return `Error::InvalidInputData`.
+    pub fn object1_celsius_as_if_emissivity(&mut self, assumed_eps: f32) -> Result<f32, Error<E>> {
+        if assumed_eps <= 0.0 || assumed_eps > 1.0 {
+            return Err(Error::InvalidInputData);
+        }
+        let object = self.object1_temperature()?.kelvin();
+        let ambient = self.ambient_temperature()?.kelvin();
+        let epsilon = self.emissivity()?;
+        if epsilon == 0.0 {
+            return Err(Error::InvalidInputData);
+        }
+        let ambient4 = ambient * ambient * ambient * ambient;
+        let object4 = object * object * object * object;
+        let rescaled4 = ambient4 + (object4 - ambient4) * epsilon / assumed_eps;
+        if rescaled4 <= 0.0 {
+            return Err(Error::InvalidInputData);
+        }
+        Ok(fourth_root(rescaled4, object) - 273.15)
+    }
+
+    /// Get the emissivity, reading it from the device only if it is not cached
+    ///
+    /// An emissivity of 0 stored in the EEPROM will return `Error::InvalidInputData`.
+    fn cached_emissivity(&mut self) -> Result<f32, Error<E>> {
+        let raw = match self.emissivity {
+            Some(raw) => raw,
+            None => {
+                let raw = self.read_u16(Register::EMISSIVITY)?;
+                self.emissivity = Some(raw);
+                raw
+            }
+        };
+        if raw == 0 {
+            return Err(Error::InvalidInputData);
+        }
+        Ok(f32::from(raw) / 65535.0)
+    }
+
+    /// Read the emissivity from the device and update the cached value
+    ///
+    /// See `fast_object_estimate()`.
+    pub fn refresh_emissivity(&mut self) -> Result<f32, Error<E>> {
+        let raw = self.read_u16(Register::EMISSIVITY)?;
+        self.emissivity = Some(raw);
+        Ok(f32::from(raw) / 65535.0)
+    }
+
+    /// Read the difference between the object 1 and ambient temperatures in millicelsius
+    ///
+    /// This only uses integer math.
+    pub fn object1_minus_ambient_millicelsius(&mut self) -> Result<i32, Error<E>> {
+        let object = self.object1_temperature()?;
+        let ambient = self.ambient_temperature()?;
+        // raw values are in 0.02K units
+        Ok((i32::from(object.raw()) - i32::from(ambient.raw())) * 20)
+    }
+
+    /// Detect whether a warm target is present in the field of view
+    ///
+    /// This reads the object 1 and ambient temperatures and returns `true`
+    /// when the object temperature exceeds the ambient temperature by more
+    /// than `delta_threshold_celsius`.
+    pub fn target_present(&mut self, delta_threshold_celsius: f32) -> Result<bool, Error<E>> {
+        let object = self.object1_temperature()?.celsius();
+        let ambient = self.ambient_temperature()?.celsius();
+        Ok(object - ambient > delta_threshold_celsius)
+    }
+
+    /// Get emissivity epsilon
+    pub fn emissivity(&mut self) -> Result<f32, Error<E>> {
+        let raw = self.read_u16(Register::EMISSIVITY)?;
+        Ok(f32::from(raw) / 65535.0)
+    }
+
+    /// Set emissivity epsilon [0.1-1.0]
+    ///
+    /// Wrong values will return `Error::InvalidInputData`.
+    pub fn set_emissivity<D: DelayNs>(
+        &mut self,
+        epsilon: f32,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        let eps = Self::emissivity_to_raw(epsilon)?;
+        let result = self.write_u16_eeprom(Register::EMISSIVITY, eps, delay);
+        self.update_cached_emissivity(eps, result)
+    }
+
+    pub(crate) fn emissivity_to_raw(epsilon: f32) -> Result<u16, Error<E>> {
+        if epsilon < 0.1 || epsilon > 1.0 {
+            return Err(Error::InvalidInputData);
+        }
+        let eps = (epsilon * 65535.0 + 0.5) as u16;
+        if eps < 6553 {
+            return Err(Error::InvalidInputData);
+        }
+        Ok(eps)
+    }
+
+    /// Get the configuration register 1
+    pub fn config_1(&mut self) -> Result<Config, Error<E>> {
+        self.read_u16(Register::CONFIG_1).map(Config::from_bits)
+    }
+
+    /// Check whether writing a configuration would change the configuration register 1
+    ///
+    /// The current configuration is read and compared to `config` without writing.
+    /// This allows skipping unnecessary EEPROM writes.
+    pub fn would_change_config(&mut self, config: &Config) -> Result<bool, Error<E>> {
+        Ok(self.read_u16(Register::CONFIG_1)? != config.as_bits())
+    }
+
+    /// Get the configuration register 1 and whether it was decoded lossily
+    ///
+    /// The returned flag is `true` if re-encoding the decoded `Config` does not
+    /// reproduce the bits read from the device.
+    /// Note that `Config` currently maps all 16 bits of the register, so every
+    /// bit pattern round-trips and the flag is always `false`.
+    /// See `config_1_reserved_bits()`.
+    pub fn config_1_checked(&mut self) -> Result<(Config, bool), Error<E>> {
+        let bits = self.read_u16(Register::CONFIG_1)?;
+        let config = Config::from_bits(bits);
+        Ok((config, config.as_bits() != bits))
+    }
+
+    /// Get the bits of the configuration register 1 not mapped by `Config`
+    ///
+    /// The mask of the reserved bits is derived from the bits `Config` decodes
+    /// and encodes. Currently all 16 bits are mapped so the mask is `0x0000`.
+    pub fn config_1_reserved_bits(&mut self) -> Result<u16, Error<E>> {
+        let bits = self.read_u16(Register::CONFIG_1)?;
+        Ok(bits & Config::RESERVED_MASK)
+    }
+
+    /// Set the configuration register 1
+    pub fn set_config_1<D: DelayNs>(
+        &mut self,
+        config: Config,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        self.write_u16_eeprom_verified(Register::CONFIG_1, config.as_bits(), delay)?;
+        // the conversion time is always a whole number of milliseconds
+        self.settling_time_ms = config.conversion_time_ms() as u32;
+        Ok(())
+    }
+
+    /// Set the configuration register 1 without verifying the write
+    ///
+    /// This skips the read-back done by `set_config_1()` and is only meant for
+    /// speed-critical batch programming where the values are verified in a
+    /// separate pass. Prefer `set_config_1()` otherwise.
+    pub fn set_config_1_unchecked<D: DelayNs>(
+        &mut self,
+        config: Config,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        self.write_u16_eeprom(Register::CONFIG_1, config.as_bits(), delay)?;
+        // the conversion time is always a whole number of milliseconds
+        self.settling_time_ms = config.conversion_time_ms() as u32;
+        Ok(())
+    }
+
+    /// Configure the filters for the lowest average current consumption
+    ///
+    /// The device draws about 1.3mA while measuring (2mA max.) and only a few µA
+    /// in sleep mode, so the average current is lowest when the device is woken
+    /// up, read once and put back to sleep (see `sleep()` and `wake_mlx90614()`).
+    /// This sets the shortest recommended FIR filter (`Fir::Step128`) and disables
+    /// the IIR filter (`Iir::Step100`) so that the first reading after waking up
+    /// is valid after a single refresh period (36ms for a single IR sensor) and
+    /// the device can go back to sleep as soon as possible.
+    /// For example, reading once per second results in an average current of
+    /// roughly 1.3mA * 36ms / 1000ms ≈ 50µA. The rest of the configuration is
+    /// preserved.
+    pub fn set_low_power_mode<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        self.modify_config_1(delay, |config| {
+            config.fir = Fir::Step128;
+            config.iir = Iir::Step100;
+        })
+    }
+
+    /// Check that the sensor selftest is enabled in the configuration register 1
+    ///
+    /// `Error::SelftestDisabled` is returned if it is disabled.
+    pub fn assert_selftest_enabled(&mut self) -> Result<(), Error<E>> {
+        if self.config_1()?.selftest_enabled() {
+            Ok(())
+        } else {
+            Err(Error::SelftestDisabled)
+        }
+    }
+
+    /// Set the amplifier gain and the FIR and IIR filters in a single write
+    ///
+    /// The configuration register 1 is read, the three settings are replaced,
+    /// and the result is written back and verified. The rest of the configuration
+    /// is preserved. The written configuration is returned.
+    /// `Error::BadEepromWrite` is returned on mismatch.
+    pub fn apply_signal_chain<D: DelayNs>(
+        &mut self,
+        gain: Gain,
+        fir: Fir,
+        iir: Iir,
+        delay: &mut D,
+    ) -> Result<Config, Error<E>> {
+        if self.eeprom_write_protected {
+            return Err(Error::WriteProtected);
+        }
+        let config = Config {
+            gain,
+            fir,
+            iir,
+            ..self.config_1()?
+        };
+        self.set_config_1(config, delay)?;
+        Ok(config)
+    }
+
+    /// Whether the repeat sensor selftest is enabled in the configuration register 1
+    pub fn repeat_selftest_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.config_1()?.repeat_sensor_selftest)
+    }
+
+    /// Enable or disable the repeat sensor selftest
+    ///
+    /// Only the repeat sensor selftest bit of the configuration register 1 is
+    /// changed, the rest of the configuration is preserved (see `modify_config_1()`).
+    pub fn set_repeat_selftest<D: DelayNs>(
+        &mut self,
+        on: bool,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        self.modify_config_1(delay, |config| config.repeat_sensor_selftest = on)
+    }
+
+    /// Set the PWM output mode
+    ///
+    /// Only the PWM mode bits of the configuration register 1 are changed,
+    /// the rest of the configuration is preserved (see `modify_config_1()`).
+    pub fn set_pwm_mode<D: DelayNs>(
+        &mut self,
+        mode: PwmMode,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        self.modify_config_1(delay, |config| config.pwm_mode = mode)
+    }
+
+    /// Modify the configuration register 1
+    ///
+    /// The configuration is read, modified by `f` and written back.
+    /// The write is verified and `Error::BadEepromWrite` is returned on mismatch.
+    pub fn modify_config_1<D: DelayNs, F: FnOnce(&mut Config)>(
+        &mut self,
+        delay: &mut D,
+        f: F,
+    ) -> Result<(), Error<E>> {
+        if self.eeprom_write_protected {
+            return Err(Error::WriteProtected);
+        }
+        let mut config = self.config_1()?;
+        f(&mut config);
+        self.set_config_1(config, delay)
+    }
+
+    /// Set the configuration register 1 and read the first settled object 1 temperature
+    ///
+    /// After writing the configuration, the settling time of the new
+    /// configuration is waited (see `settling_time_ms()`) before reading.
+    pub fn set_config_and_read<D: DelayNs>(
+        &mut self,
+        config: Config,
+        delay: &mut D,
+    ) -> Result<Temperature, Error<E>> {
+        self.set_config_1(config, delay)?;
+        delay.delay_ms(self.settling_time_ms);
+        self.object1_temperature()
+    }
+
+    /// Recommended time to wait after the last configuration change in milliseconds
+    ///
+    /// This is the conversion time of the configuration last written through
+    /// this driver (see `Config::conversion_time_ms()`). Readings taken earlier
+    /// may not reflect the object temperature yet.
+    /// Returns 0 if no configuration has been written.
+    pub fn settling_time_ms(&self) -> u32 {
+        self.settling_time_ms
+    }
+
+    /// Configure the filters for the highest measurement accuracy
+    ///
+    /// This sets the longest FIR filter (`Fir::Step1024`) and the strongest IIR
+    /// filter (`Iir::Step13`), which result in the lowest noise but also the
+    /// slowest response. The rest of the configuration is preserved.
+    pub fn set_highest_accuracy<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        self.modify_config_1(delay, |config| {
+            config.fir = Fir::Step1024;
+            config.iir = Iir::Step13;
+        })
+    }
+
+    /// Set up the PWM output
+    ///
+    /// This writes the object temperature range `min`-`max` to the TOMIN and TOMAX
+    /// registers, enables the PWM output with the given period in the PWMCTRL
+    /// register and sets the PWM mode in the configuration register 1.
+    /// Every write is verified and `Error::BadEepromWrite` is returned on mismatch.
+    ///
+    /// `PwmMode::Tobj2` uses the single PWM mode where the period can be set
+    /// in 1.024ms steps up to 131.072ms. The rest of the modes use the extended
+    /// PWM mode where the period can be set in 2.048ms steps up to 262.144ms.
+    /// The SDA pin driver and PWM repetition settings are preserved.
+    ///
+    /// Wrong values will return `Error::InvalidInputData`.
+    pub fn setup_pwm<D: DelayNs>(
+        &mut self,
+        mode: PwmMode,
+        min: Temperature,
+        max: Temperature,
+        period_ms: f32,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        if min.raw() >= max.raw() || max.raw() & 0x8000 != 0 {
+            return Err(Error::InvalidInputData);
+        }
+        let single = mode == PwmMode::Tobj2;
+        let step_ms = if single { 1.024 } else { 2.048 };
+        let period = (period_ms / step_ms + 0.5) as u16;
+        if period == 0 || period > 128 {
+            return Err(Error::InvalidInputData);
+        }
+        if self.eeprom_write_protected {
+            return Err(Error::WriteProtected);
+        }
+        let pwmctrl = self.read_u16(Register::PWMCTRL)?;
+        let config = Config {
+            pwm_mode: mode,
+            ..self.config_1()?
+        };
+        // keep SDA pin driver and PWM repetition bits
+        let mut pwmctrl = (pwmctrl & 0b0000_0001_1111_0100) | PWMCTRL_PWM_ENABLE;
+        if single {
+            pwmctrl |= PWMCTRL_SINGLE_PWM_MODE;
+        }
+        // a period of 128 is encoded as 0
+        pwmctrl |= (period & 0x7F) << 9;
+        // TOMIN and TOMAX use a resolution of 0.01K
+        self.write_u16_eeprom_verified(Register::TOMIN, min.raw() * 2, delay)?;
+        self.write_u16_eeprom_verified(Register::TOMAX, max.raw() * 2, delay)?;
+        self.write_u16_eeprom_verified(Register::PWMCTRL, pwmctrl, delay)?;
+        self.set_config_1(config, delay)
+    }
+
+    /// Set up the thermal relay output with hysteresis
+    ///
+    /// This writes the `low` and `high` thresholds to the TOMIN and TOMAX
+    /// registers and enables the thermal relay mode in the PWMCTRL register.
+    /// The output turns on when the object temperature rises above `high` and
+    /// turns off when it falls below `low`.
+    /// Every write is verified and `Error::BadEepromWrite` is returned on mismatch.
+    /// The rest of the PWMCTRL settings are preserved.
+    ///
+    /// `low` must be lower than `high`. Wrong values will return `Error::InvalidInputData`.
+    pub fn set_relay_hysteresis<D: DelayNs>(
+        &mut self,
+        low: Temperature,
+        high: Temperature,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        if low.raw() >= high.raw() || high.raw() & 0x8000 != 0 {
+            return Err(Error::InvalidInputData);
+        }
+        if self.eeprom_write_protected {
+            return Err(Error::WriteProtected);
+        }
+        let pwmctrl = self.read_u16(Register::PWMCTRL)?;
+        let pwmctrl = pwmctrl | PWMCTRL_PWM_ENABLE | PWMCTRL_THERMAL_RELAY;
+        // TOMIN and TOMAX use a resolution of 0.01K
+        self.write_u16_eeprom_verified(Register::TOMIN, low.raw() * 2, delay)?;
+        self.write_u16_eeprom_verified(Register::TOMAX, high.raw() * 2, delay)?;
+        self.write_u16_eeprom_verified(Register::PWMCTRL, pwmctrl, delay)
+    }
+
+    /// Read all configuration EEPROM registers
+    ///
+    /// This reads the configuration register 1, emissivity, PWM control,
+    /// TOMIN, TOMAX and address registers.
+    pub fn read_device_config(&mut self) -> Result<DeviceConfig, Error<E>> {
+        let config_1 = self.config_1()?;
+        let emissivity = self.emissivity()?;
+        let pwmctrl = self.read_u16(Register::PWMCTRL)?;
+        // TOMIN and TOMAX use a resolution of 0.01K
+        let tomin = Temperature(self.read_u16(Register::TOMIN)? / 2);
+        let tomax = Temperature(self.read_u16(Register::TOMAX)? / 2);
+        let address = self.read_u16(Register::ADDRESS)? as u8;
+        Ok(DeviceConfig {
+            config_1,
+            emissivity,
+            pwmctrl,
+            tomin,
+            tomax,
+            address,
+        })
+    }
+
+    /// Write all configuration EEPROM registers
+    ///
+    /// The registers are written in the order of `read_device_config()` and
+    /// every write is verified. `Error::BadEepromWrite` is returned on mismatch.
+    ///
+    /// As with `set_address()`, the driver uses the new address afterwards.
+    /// Wrong values will return `Error::InvalidInputData`.
+    pub fn write_device_config<D: DelayNs>(
+        &mut self,
+        config: &DeviceConfig,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        let emissivity = Self::emissivity_to_raw(config.emissivity)?;
+        let address = Self::get_address(SlaveAddr::Alternative(config.address), DEV_ADDR)?;
+        if config.tomin.raw() & 0x8000 != 0 || config.tomax.raw() & 0x8000 != 0 {
+            return Err(Error::InvalidInputData);
+        }
+        self.set_config_1(config.config_1, delay)?;
+        let result = self.write_u16_eeprom_verified(Register::EMISSIVITY, emissivity, delay);
+        self.update_cached_emissivity(emissivity, result)?;
+        self.write_u16_eeprom_verified(Register::PWMCTRL, config.pwmctrl, delay)?;
+        self.write_u16_eeprom_verified(Register::TOMIN, config.tomin.raw() * 2, delay)?;
+        self.write_u16_eeprom_verified(Register::TOMAX, config.tomax.raw() * 2, delay)?;
+        self.write_u16_eeprom_verified(Register::ADDRESS, u16::from(address), delay)?;
+        self.address = address;
+        Ok(())
+    }
+
+    /// Program the emissivity, configuration register 1 and address in one go
+    ///
+    /// The values are written in this order and every write is verified.
+    /// The sequence stops at the first mismatch returning `Error::BadEepromWrite`.
+    ///
+    /// As with `set_address()`, the driver uses the new address afterwards.
+    /// Wrong values will return `Error::InvalidInputData` before anything is written.
+    pub fn factory_program<D: DelayNs>(
+        &mut self,
+        address: SlaveAddr,
+        emissivity: f32,
+        config: Config,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        let emissivity = Self::emissivity_to_raw(emissivity)?;
+        let address = Self::get_address(address, DEV_ADDR)?;
+        let result = self.write_u16_eeprom_verified(Register::EMISSIVITY, emissivity, delay);
+        self.update_cached_emissivity(emissivity, result)?;
+        self.set_config_1(config, delay)?;
+        self.write_u16_eeprom_verified(Register::ADDRESS, u16::from(address), delay)?;
+        self.address = address;
+        Ok(())
+    }
+
+    /// Change the device address after putting the device in a known state
+    ///
+    /// To avoid changing the address while the device is measuring, the device
+    /// is first put to sleep and woken up again with the SCL/SDA pins
+    /// (see `sleep()` and `wake_mlx90614()`). The pins must be usable while the
+    /// I²C bus is connected, e.g. open-drain pins shared with the bus.
+    /// Then the address is written to the EEPROM and verified.
+    /// `Error::BadEepromWrite` is returned on mismatch and `Error::Pin` if
+    /// setting a pin fails.
+    ///
+    /// The device only responds to the new address after a power cycle.
+    pub fn set_address_safe<D, PinE, SclPin, SdaPin>(
+        &mut self,
+        address: SlaveAddr,
+        scl: &mut SclPin,
+        sda: &mut SdaPin,
+        delay: &mut D,
+    ) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+        SclPin: OutputPin<Error = PinE>,
+        SdaPin: OutputPin<Error = PinE>,
+    {
+        let address = Self::get_address(address, DEV_ADDR)?;
+        if self.eeprom_write_protected {
+            return Err(Error::WriteProtected);
+        }
+        self.sleep()?;
+        wake_mlx90614(scl, sda, delay).map_err(|_| Error::Pin)?;
+        self.write_u16_eeprom_verified(Register::ADDRESS, u16::from(address), delay)?;
+        self.address = address;
+        Ok(())
+    }
+
+    /// Capture the contents of all RAM and EEPROM registers
+    ///
+    /// This reads the 32 RAM registers followed by the 32 EEPROM registers.
+    /// See `DeviceState::frame()` for replaying the state in tests.
+    pub fn capture_state(&mut self) -> Result<DeviceState, Error<E>> {
+        let mut state = DeviceState {
+            ram: [0; 32],
+            eeprom: [0; 32],
+        };
+        for (i, word) in state.ram.iter_mut().enumerate() {
+            *word = self.read_u16(i as u8)?;
+        }
+        for (i, word) in state.eeprom.iter_mut().enumerate() {
+            *word = self.read_u16(Register::TOMAX + i as u8)?;
+        }
+        Ok(state)
+    }
+
+    /// Read all known registers paired with their names
+    ///
+    /// This reads the RAM registers, the EEPROM registers and the flags
+    /// in address order, e.g. for register dump tools.
+    pub fn register_map(&mut self) -> Result<Vec<(&'static str, u16), 24>, Error<E>> {
+        let mut map = Vec::new();
+        for (name, register) in REGISTER_NAMES {
+            let value = self.read_u16(register)?;
+            // cannot fail as there are fewer known registers than the capacity
+            let _ = map.push((name, value));
+        }
+        Ok(map)
+    }
+
+    /// Get the device ID
+    ///
+    /// The first ID word is the most significant one. See `device_id_be()`.
+    ///
+    /// The ID is only read from the device on the first call and cached afterwards.
+    /// Use `refresh_device_id()` to read it again.
+    pub fn device_id(&mut self) -> Result<u64, Error<E>> {
+        match self.device_id {
+            Some(id) => Ok(id),
+            None => self.refresh_device_id(),
+        }
+    }
+
+    /// Read the device ID from the device and update the cached value
+    pub fn refresh_device_id(&mut self) -> Result<u64, Error<E>> {
+        let id = self.device_id_be()?;
+        self.device_id = Some(id);
+        Ok(id)
+    }
+
+    /// Get the device ID assembled with the first ID word as the most significant one
+    pub fn device_id_be(&mut self) -> Result<u64, Error<E>> {
+        self.read_device_id(true)
+    }
+
+    /// Get the device ID assembled with the first ID word as the least significant one
+    ///
+    /// Some clone devices report the ID words in reverse order.
+    pub fn device_id_le(&mut self) -> Result<u64, Error<E>> {
+        self.read_device_id(false)
+    }
+
+    fn read_device_id(&mut self, first_word_msb: bool) -> Result<u64, Error<E>> {
+        let mut id = 0;
+        for i in 0..4 {
+            let part = self.read_u16(Register::ID0 + i)?;
+            let shift = if first_word_msb { 3 - i } else { i };
+            id |= u64::from(part) << (16 * shift);
+        }
+        Ok(id)
+    }
+
+    /// Guess the device variant
+    ///
+    /// The device ID is a unique serial number and does not encode the variant,
+    /// so this is only a heuristic:
+    /// - An ID consisting only of zeros or ones is not programmed, which is
+    ///   typical of clone devices. `"Unknown"` is returned.
+    /// - Otherwise the IR sensor setting of the configuration register 1 tells
+    ///   single zone devices (`"MLX90614xAx"`) apart from dual zone devices
+    ///   (`"MLX90614xBx"`, also used by the gradient compensated `xCx` variants).
+    ///
+    /// The supply voltage and field of view variant letters cannot be read
+    /// from the device and are reported as `x`.
+    pub fn variant_name(&mut self) -> Result<&'static str, Error<E>> {
+        let id = self.device_id()?;
+        if id == 0 || id == u64::MAX {
+            return Ok("Unknown");
+        }
+        if self.config_1()?.dual_ir_sensor {
+            Ok("MLX90614xBx")
+        } else {
+            Ok("MLX90614xAx")
+        }
     }
 
-    /// Get emissivity epsilon
-    pub fn emissivity(&mut self) -> Result<f32, Error<E>> {
-        let raw = self.read_u16(Register::EMISSIVITY)?;
-        Ok(f32::from(raw) / 65535.0)
+    /// Read the device flags
+    pub fn flags(&mut self) -> Result<Flags, Error<E>> {
+        self.read_u16(Register::FLAGS).map(Flags::from_bits)
     }
 
-    /// Set emissivity epsilon [0.1-1.0]
+    /// Determine the EEPROM write delay needed by the device
     ///
-    /// Wrong values will return `Error::InvalidInputData`.
-    pub fn set_emissivity<D: DelayNs>(
+    /// The emissivity is rewritten with delays from `start_ms` to `max_ms` and the
+    /// first delay that works is stored in the driver and returned.
+    /// If none works, `Error::BadEepromWrite` is returned. On any error, the
+    /// original emissivity is written again as a best effort.
+    /// A `start_ms` bigger than `max_ms` will return `Error::InvalidInputData`.
+    pub fn calibrate_write_delay<D: DelayNs>(
         &mut self,
-        epsilon: f32,
         delay: &mut D,
-    ) -> Result<(), Error<E>> {
-        if epsilon < 0.1 || epsilon > 1.0 {
+        start_ms: u8,
+        max_ms: u8,
+    ) -> Result<u8, Error<E>> {
+        if start_ms > max_ms {
             return Err(Error::InvalidInputData);
         }
-        let eps = (epsilon * 65535.0 + 0.5) as u16;
-        if eps < 6553 {
-            return Err(Error::InvalidInputData);
+        if self.eeprom_write_protected {
+            return Err(Error::WriteProtected);
+        }
+        let original = self.read_u16(Register::EMISSIVITY)?;
+        self.emissivity = None;
+        let result = self.find_write_delay(delay, start_ms, max_ms, original);
+        if result.is_err() && self.write_u16(Register::EMISSIVITY, 0).is_ok() {
+            delay.delay_ms(u32::from(max_ms));
+            if self.write_u16(Register::EMISSIVITY, original).is_ok() {
+                delay.delay_ms(u32::from(max_ms));
+            }
         }
-        self.write_u16_eeprom(Register::EMISSIVITY, eps, delay)
+        result
     }
 
-    /// Get the configuration register 1
-    pub fn config_1(&mut self) -> Result<Config, Error<E>> {
-        self.read_u16(Register::CONFIG_1).map(Config::from_bits)
+    fn find_write_delay<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        start_ms: u8,
+        max_ms: u8,
+        original: u16,
+    ) -> Result<u8, Error<E>> {
+        for delay_ms in start_ms..=max_ms {
+            self.write_u16(Register::EMISSIVITY, 0)?;
+            delay.delay_ms(u32::from(delay_ms));
+            if self.flags()?.eeprom_busy {
+                // let the erase complete before restoring the value
+                delay.delay_ms(u32::from(max_ms));
+                self.write_u16(Register::EMISSIVITY, original)?;
+            } else {
+                self.write_u16(Register::EMISSIVITY, original)?;
+                delay.delay_ms(u32::from(delay_ms));
+                if !self.flags()?.eeprom_busy && self.read_u16(Register::EMISSIVITY)? == original {
+                    self.eeprom_write_delay_ms = delay_ms;
+                    return Ok(delay_ms);
+                }
+            }
+            delay.delay_ms(u32::from(max_ms));
+        }
+        Err(Error::BadEepromWrite)
     }
 
-    /// Set the configuration register 1
-    pub fn set_config_1<D: DelayNs>(
+    /// Read the device flags as bit flags
+    ///
+    /// Undocumented bits are discarded.
+    #[cfg(feature = "bitflags")]
+    pub fn flag_bits(&mut self) -> Result<FlagBits, Error<E>> {
+        self.read_u16(Register::FLAGS)
+            .map(FlagBits::from_bits_truncate)
+    }
+
+    /// Stream the raw IR data of both channels to a writer
+    ///
+    /// The configuration register 1 is read first and `count` frames are then
+    /// read one refresh period apart (depending on the FIR and IR sensor
+    /// configuration). Each frame is written as the channel 1 followed by the
+    /// channel 2 raw IR data, each as a little-endian `i16`.
+    /// `Error::Write` is returned if writing fails.
+    #[cfg(feature = "embedded-io")]
+    pub fn stream_raw_ir<W: embedded_io::Write, D: DelayNs>(
         &mut self,
-        config: Config,
+        writer: &mut W,
         delay: &mut D,
+        count: u32,
     ) -> Result<(), Error<E>> {
-        self.write_u16_eeprom(Register::CONFIG_1, config.as_bits(), delay)?;
-        delay.delay_ms(u32::from(self.eeprom_write_delay_ms));
-        if config == self.config_1()? {
-            Ok(())
-        } else {
-            Err(Error::BadEepromWrite)
+        let config = self.config_1()?;
+        let period_us = config.refresh_period_us();
+        for i in 0..count {
+            if i != 0 {
+                delay.delay_us(period_us);
+            }
+            let ir1 = self.raw_ir_channel1()?.to_le_bytes();
+            let ir2 = self.raw_ir_channel2()?.to_le_bytes();
+            let frame = [ir1[0], ir1[1], ir2[0], ir2[1]];
+            writer.write_all(&frame).map_err(|_| Error::Write)?;
         }
+        Ok(())
     }
 
-    /// Get the device ID
-    pub fn device_id(&mut self) -> Result<u64, Error<E>> {
-        let mut id = 0;
-        for i in 0..4 {
-            let part = self.read_u16(Register::ID0 + i)?;
-            let part = u64::from(part) << (16 * (3 - i));
-            id |= part;
+    /// Read a full status report of the device
+    ///
+    /// This reads the flags, device ID, configuration register 1, emissivity,
+    /// ambient and object 1 temperatures in a single sequence.
+    /// The object 2 temperature is only read if the configuration reports a
+    /// dual IR sensor.
+    pub fn status_report(&mut self) -> Result<StatusReport, Error<E>> {
+        let flags = self.flags()?;
+        let device_id = self.device_id()?;
+        let config = self.config_1()?;
+        let emissivity = self.emissivity()?;
+        let ambient = self.ambient_temperature()?;
+        let object1 = self.object1_temperature()?;
+        let object2 = if config.dual_ir_sensor {
+            Some(self.object2_temperature()?)
+        } else {
+            None
+        };
+        Ok(StatusReport {
+            device_id,
+            config,
+            emissivity,
+            ambient,
+            object1,
+            object2,
+            flags,
+        })
+    }
+}
+
+const PWMCTRL_SINGLE_PWM_MODE: u16 = 1;
+const PWMCTRL_PWM_ENABLE: u16 = 1 << 1;
+const PWMCTRL_THERMAL_RELAY: u16 = 1 << 3;
+/// Names of all known registers in address order
+const REGISTER_NAMES: [(&str, u8); 17] = [
+    ("RAW_IR1", Register::RAW_IR1),
+    ("RAW_IR2", Register::RAW_IR2),
+    ("TA", Register::TA),
+    ("TOBJ1", Register::TOBJ1),
+    ("TOBJ2", Register::TOBJ2),
+    ("TOMAX", Register::TOMAX),
+    ("TOMIN", Register::TOMIN),
+    ("PWMCTRL", Register::PWMCTRL),
+    ("TARANGE", Register::TARANGE),
+    ("EMISSIVITY", Register::EMISSIVITY),
+    ("CONFIG_1", Register::CONFIG_1),
+    ("ADDRESS", Register::ADDRESS),
+    ("ID0", Register::ID0),
+    ("ID1", Register::ID0 + 1),
+    ("ID2", Register::ID0 + 2),
+    ("ID3", Register::ID0 + 3),
+    ("FLAGS", Register::FLAGS),
+];
+/// Absolute difference between two temperatures in degrees
+///
+/// `f32::abs()` is not available in `no_std` on older compilers.
+fn difference_celsius(a: Temperature, b: Temperature) -> f32 {
+    f32::from(a.raw().abs_diff(b.raw())) * 0.02
+}
+
+/// Fourth root of a positive value with Newton's method starting from `guess`
+///
+/// `f32::sqrt()` is not available in `no_std` on older compilers.
+fn fourth_root(value: f32, guess: f32) -> f32 {
+    let mut y = if guess > 0.0 { guess } else { 1.0 };
+    for _ in 0..20 {
+        y = (3.0 * y + value / (y * y * y)) / 4.0;
+    }
+    y
+}
+
+/// Emissivity independent raw IR scale in K⁴ per count from readings taken together
+///
+/// Returns `None` if the raw IR value is 0 or the scale is not positive.
+fn derive_ir_scale(
+    object_kelvin: f32,
+    ambient_kelvin: f32,
+    raw_ir: i16,
+    epsilon: f32,
+) -> Option<f32> {
+    if raw_ir == 0 {
+        return None;
+    }
+    let object4 = object_kelvin * object_kelvin * object_kelvin * object_kelvin;
+    let ambient4 = ambient_kelvin * ambient_kelvin * ambient_kelvin * ambient_kelvin;
+    let scale = epsilon * (object4 - ambient4) / f32::from(raw_ir);
+    if scale > 0.0 {
+        Some(scale)
+    } else {
+        None
+    }
+}
+
+/// Object temperature in Kelvin from the raw IR data with the Stefan-Boltzmann law
+///
+/// Returns `None` if the ambient temperature or the result would be below 0K.
+fn ir_object_kelvin(ambient_kelvin: f32, raw_ir: i16, scale: f32, epsilon: f32) -> Option<f32> {
+    if ambient_kelvin <= 0.0 {
+        return None;
+    }
+    let ambient4 = ambient_kelvin * ambient_kelvin * ambient_kelvin * ambient_kelvin;
+    let object4 = ambient4 + scale * f32::from(raw_ir) / epsilon;
+    if object4 <= 0.0 {
+        return None;
+    }
+    Some(fourth_root(object4, ambient_kelvin))
+}
+
+impl<I2C> Mlx9061x<I2C, ic::Mlx90614> {
+    /// Convert this driver into an MLX90615 driver
+    ///
+    /// The I²C bus, slave address, EEPROM write delay and write protection
+    /// setting are kept. The cached values are cleared.
+    pub fn into_mlx90615(self) -> Mlx9061x<I2C, ic::Mlx90615> {
+        Mlx9061x {
+            i2c: self.i2c,
+            eeprom_write_delay_ms: self.eeprom_write_delay_ms,
+            address: self.address,
+            sample_index: self.sample_index,
+            device_id: None,
+            last_object1: None,
+            peak_object1: None,
+            thermostat_on: false,
+            settling_time_ms: 0,
+            eeprom_write_protected: self.eeprom_write_protected,
+            ta_range: None,
+            emissivity: None,
+            ir_scale: None,
+            calibration: (1.0, 0.0),
+            filter_window: self.filter_window,
+            filter_samples: heapless::Deque::new(),
+            ema_object1: None,
+            _ic: PhantomData,
         }
-        Ok(id)
     }
 }
 
@@ -139,6 +1631,122 @@ pub fn wake_mlx90614<E, SclPin: OutputPin<Error = E>, SdaPin: OutputPin<Error =
     sda.set_high()
 }
 
+/// Write the same configuration register 1 to several MLX90614 devices
+///
+/// Each device is written and verified as in `set_config_1()` and the result
+/// for each address is returned in the same order.
+pub fn configure_all<E, I2C: I2c<Error = E>, D: DelayNs, const N: usize>(
+    i2c: &mut I2C,
+    addresses: &[u8; N],
+    config: &Config,
+    eeprom_write_delay_ms: u8,
+    delay: &mut D,
+) -> [Result<(), Error<E>>; N] {
+    addresses.map(|address| {
+        Mlx9061x::new_mlx90614(
+            &mut *i2c,
+            SlaveAddr::Alternative(address),
+            eeprom_write_delay_ms,
+        )
+        .and_then(|mut sensor| sensor.set_config_1(*config, delay))
+    })
+}
+
+/// Read the raw IR data of both channels of several MLX90614 devices
+///
+/// The channel 1 and channel 2 raw IR data of each device is returned in the
+/// same order as the addresses.
+///
+/// The devices are read one after another, so the readings are not taken at
+/// the same time. Each device takes two word reads, which is roughly 1.1ms
+/// at 100kHz, so the last device is read about `(N - 1) * 1.1ms` after the first.
+/// Additionally, each device updates its data at its own refresh rate.
+pub fn read_raw_ir_array<E, I2C: I2c<Error = E>, const N: usize>(
+    i2c: &mut I2C,
+    addresses: &[u8; N],
+) -> [Result<(i16, i16), Error<E>>; N] {
+    addresses.map(|address| {
+        let mut sensor = Mlx9061x::new_mlx90614(&mut *i2c, SlaveAddr::Alternative(address), 0)?;
+        let ir1 = sensor.raw_ir_channel1()?;
+        let ir2 = sensor.raw_ir_channel2()?;
+        Ok((ir1, ir2))
+    })
+}
+
+/// Read the ambient temperature of several MLX90614 devices and return the spread
+///
+/// The spread is the difference between the highest and the lowest ambient
+/// temperature in celsius. A large spread at power-up flags a sensor reporting
+/// an ambient temperature diverging from the rest.
+/// The first error encountered is returned. No addresses will return
+/// `Error::InvalidInputData`.
+pub fn ambient_spread<E, I2C: I2c<Error = E>>(
+    i2c: &mut I2C,
+    addresses: &[u8],
+) -> Result<f32, Error<E>> {
+    let mut range: Option<(f32, f32)> = None;
+    for &address in addresses {
+        let mut sensor = Mlx9061x::new_mlx90614(&mut *i2c, SlaveAddr::Alternative(address), 0)?;
+        let t = sensor.ambient_temperature()?.celsius();
+        range = Some(match range {
+            Some((min, max)) => (min.min(t), max.max(t)),
+            None => (t, t),
+        });
+    }
+    match range {
+        Some((min, max)) => Ok(max - min),
+        None => Err(Error::InvalidInputData),
+    }
+}
+
+/// Recommend the lightest FIR/IIR filter settings meeting a noise target
+///
+/// The noise target is given as the standard deviation of the object temperature
+/// readings in degrees celsius. This is a heuristic: The noise is assumed to be
+/// 0.05°C with `Fir::Step1024` and `Iir::Step100`, the variance to scale inversely with
+/// the FIR length and to be reduced by a factor of `a1 / (2 - a1)` by the IIR filter.
+/// Among the recommended FIR settings (128 and above), the combination with the
+/// shortest conversion time for a single IR sensor meeting the target is returned.
+/// If the target cannot be met, the heaviest filtering is returned.
+pub fn recommend_filters(noise_std_celsius: f32) -> (Fir, Iir) {
+    const FIRS: [(Fir, f32); 4] = [
+        (Fir::Step128, 128.0),
+        (Fir::Step256, 256.0),
+        (Fir::Step512, 512.0),
+        (Fir::Step1024, 1024.0),
+    ];
+    const IIRS: [Iir; 8] = [
+        Iir::Step100,
+        Iir::Step80,
+        Iir::Step67,
+        Iir::Step57,
+        Iir::Step50,
+        Iir::Step25,
+        Iir::Step17,
+        Iir::Step13,
+    ];
+    let target_variance = noise_std_celsius * noise_std_celsius;
+    let mut best: Option<(Fir, Iir, f32)> = None;
+    for (fir, length) in FIRS {
+        for iir in IIRS {
+            let a1 = iir.a1();
+            let variance = 0.05 * 0.05 * (1024.0 / length) * (a1 / (2.0 - a1));
+            if variance > target_variance {
+                continue;
+            }
+            let time = fir.refresh_period_ms(false) * f32::from(iir.settling_samples());
+            let is_faster = match best {
+                Some((_, _, best_time)) => time < best_time,
+                None => true,
+            };
+            if is_faster {
+                best = Some((fir, iir, time));
+            }
+        }
+    }
+    best.map_or((Fir::Step1024, Iir::Step13), |(fir, iir, _)| (fir, iir))
+}
+
 /// IIR filter settings (Bits 0-2)
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -161,6 +1769,52 @@ pub enum Iir {
     Step57 = 0b111,
 }
 
+impl Iir {
+    fn label(self) -> &'static str {
+        match self {
+            Iir::Step50 => "50%",
+            Iir::Step25 => "25%",
+            Iir::Step17 => "17%",
+            Iir::Step13 => "13%",
+            Iir::Step100 => "100%",
+            Iir::Step80 => "80%",
+            Iir::Step67 => "67%",
+            Iir::Step57 => "57%",
+        }
+    }
+
+    /// Weight of the new sample
+    fn a1(self) -> f32 {
+        match self {
+            Iir::Step100 => 1.0,
+            Iir::Step80 => 0.8,
+            Iir::Step67 => 2.0 / 3.0,
+            Iir::Step57 => 4.0 / 7.0,
+            Iir::Step50 => 0.5,
+            Iir::Step25 => 0.25,
+            Iir::Step17 => 1.0 / 6.0,
+            Iir::Step13 => 0.125,
+        }
+    }
+
+    /// Number of samples needed to settle to 99% of a step input
+    ///
+    /// With the IIR filter output `y = a1 * x + b * y'`, this is the smallest
+    /// `n` for which `b^n <= 0.01`.
+    fn settling_samples(self) -> u8 {
+        match self {
+            Iir::Step100 => 1,
+            Iir::Step80 => 3,
+            Iir::Step67 => 5,
+            Iir::Step57 => 6,
+            Iir::Step50 => 7,
+            Iir::Step25 => 17,
+            Iir::Step17 => 26,
+            Iir::Step13 => 35,
+        }
+    }
+}
+
 /// PWM mode configuration (Bits 4-5)
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -175,6 +1829,17 @@ pub enum PwmMode {
     Tobj1Tobj2 = 0b11,
 }
 
+impl PwmMode {
+    fn label(self) -> &'static str {
+        match self {
+            PwmMode::TaTobj1 => "Ta, Tobj1",
+            PwmMode::TaTobj2 => "Ta, Tobj2",
+            PwmMode::Tobj2 => "Tobj2",
+            PwmMode::Tobj1Tobj2 => "Tobj1, Tobj2",
+        }
+    }
+}
+
 /// FIR filter settings (Bits 8-10)
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -197,6 +1862,48 @@ pub enum Fir {
     Step1024 = 0b111,
 }
 
+impl Fir {
+    fn label(self) -> &'static str {
+        match self {
+            Fir::Step8 => "8",
+            Fir::Step16 => "16",
+            Fir::Step32 => "32",
+            Fir::Step64 => "64",
+            Fir::Step128 => "128",
+            Fir::Step256 => "256",
+            Fir::Step512 => "512",
+            Fir::Step1024 => "1024",
+        }
+    }
+
+    /// Measurement refresh period in milliseconds
+    ///
+    /// The FIR lengths below 128 are not recommended and use the timing of
+    /// `Step128` as an upper bound.
+    fn refresh_period_ms(self, dual_ir_sensor: bool) -> f32 {
+        match (self, dual_ir_sensor) {
+            (Fir::Step8 | Fir::Step16 | Fir::Step32 | Fir::Step64 | Fir::Step128, false) => 36.0,
+            (Fir::Step8 | Fir::Step16 | Fir::Step32 | Fir::Step64 | Fir::Step128, true) => 53.0,
+            (Fir::Step256, false) => 44.0,
+            (Fir::Step256, true) => 53.0,
+            (Fir::Step512, false) => 60.0,
+            (Fir::Step512, true) => 90.0,
+            (Fir::Step1024, false) => 93.0,
+            (Fir::Step1024, true) => 138.0,
+        }
+    }
+}
+
+/// IR sensor topology (Bit 6)
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IrTopology {
+    /// Single IR sensor
+    Single,
+    /// Dual IR sensor
+    Dual,
+}
+
 /// Amplifier gain settings (Bits 11-13)
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -219,6 +1926,20 @@ pub enum Gain {
     Gain100Alt = 0b111,
 }
 
+impl Gain {
+    fn label(self) -> &'static str {
+        match self {
+            Gain::Gain1 => "1",
+            Gain::Gain3 => "3",
+            Gain::Gain6 => "6",
+            Gain::Gain12_5 => "12.5",
+            Gain::Gain25 => "25",
+            Gain::Gain50 => "50",
+            Gain::Gain100 | Gain::Gain100Alt => "100",
+        }
+    }
+}
+
 /// Configuration register 1
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -243,7 +1964,166 @@ pub struct Config {
     pub sensor_selftest_disabled: bool,
 }
 
+impl Default for Config {
+    /// Typical factory setting of the configuration register 1 (0x9FB4)
+    ///
+    /// Note that the Ks, Kt2 and gain settings are part of the factory calibration
+    /// and may differ between devices.
+    fn default() -> Self {
+        Config::from_bits(0x9FB4)
+    }
+}
+
 impl Config {
+    const RESERVED_MASK: u16 = 0x0000;
+
+    /// Names and values of all the fields as text
+    ///
+    /// The fields are in the register bit order. For example: `("IIR", "100%")`.
+    pub fn describe(&self) -> [(&'static str, &'static str); 9] {
+        fn on_off(value: bool) -> &'static str {
+            if value {
+                "On"
+            } else {
+                "Off"
+            }
+        }
+        fn sign(negative: bool) -> &'static str {
+            if negative {
+                "Negative"
+            } else {
+                "Positive"
+            }
+        }
+        [
+            ("IIR", self.iir.label()),
+            (
+                "Repeat sensor selftest",
+                on_off(self.repeat_sensor_selftest),
+            ),
+            ("PWM mode", self.pwm_mode.label()),
+            (
+                "IR sensor",
+                if self.dual_ir_sensor {
+                    "Dual"
+                } else {
+                    "Single"
+                },
+            ),
+            ("Ks sign", sign(self.ks_sign_negative)),
+            ("FIR", self.fir.label()),
+            ("Gain", self.gain.label()),
+            ("Kt2 sign", sign(self.kt2_sign_negative)),
+            ("Sensor selftest", on_off(!self.sensor_selftest_disabled)),
+        ]
+    }
+
+    /// Names of the fields differing from another configuration
+    ///
+    /// The names are the same as in `describe()` and in the register bit order.
+    pub fn diff(&self, other: &Config) -> Vec<&'static str, 9> {
+        let fields = [
+            ("IIR", self.iir != other.iir),
+            (
+                "Repeat sensor selftest",
+                self.repeat_sensor_selftest != other.repeat_sensor_selftest,
+            ),
+            ("PWM mode", self.pwm_mode != other.pwm_mode),
+            ("IR sensor", self.dual_ir_sensor != other.dual_ir_sensor),
+            ("Ks sign", self.ks_sign_negative != other.ks_sign_negative),
+            ("FIR", self.fir != other.fir),
+            ("Gain", self.gain != other.gain),
+            (
+                "Kt2 sign",
+                self.kt2_sign_negative != other.kt2_sign_negative,
+            ),
+            (
+                "Sensor selftest",
+                self.sensor_selftest_disabled != other.sensor_selftest_disabled,
+            ),
+        ];
+        let mut differing = Vec::new();
+        for (name, _) in fields.iter().filter(|(_, differs)| *differs) {
+            // cannot fail as there are only 9 fields
+            let _ = differing.push(*name);
+        }
+        differing
+    }
+
+    /// Compact identifier of the configuration
+    ///
+    /// This is the CRC-8 (see `crc8()`) of the register value in little-endian
+    /// byte order. It is deterministic but different configurations may share
+    /// the same identifier.
+    pub fn profile_id(&self) -> u8 {
+        crc8(&self.as_bits().to_le_bytes())
+    }
+
+    /// Whether both the Ks and Kt2 coefficient signs are positive
+    pub fn coefficients_positive(&self) -> bool {
+        !self.ks_sign_negative && !self.kt2_sign_negative
+    }
+
+    /// Whether the sensor selftest is enabled
+    pub fn selftest_enabled(&self) -> bool {
+        !self.sensor_selftest_disabled
+    }
+
+    /// Measurement refresh period in microseconds
+    fn refresh_period_us(&self) -> u32 {
+        (self.fir.refresh_period_ms(self.dual_ir_sensor) * 1000.0) as u32
+    }
+
+    /// Names of the fields differing from the default configuration
+    ///
+    /// See `diff()` and `Config::default()`.
+    pub fn non_default_settings(&self) -> Vec<&'static str, 9> {
+        self.diff(&Config::default())
+    }
+
+    /// Single or dual IR sensor setting
+    pub fn ir_topology(&self) -> IrTopology {
+        if self.dual_ir_sensor {
+            IrTopology::Dual
+        } else {
+            IrTopology::Single
+        }
+    }
+
+    /// Set the single or dual IR sensor setting
+    pub fn set_ir_topology(&mut self, topology: IrTopology) {
+        self.dual_ir_sensor = topology == IrTopology::Dual;
+    }
+
+    /// Whether the configured PWM mode outputs the object 2 temperature
+    pub fn pwm_includes_object2(&self) -> bool {
+        match self.pwm_mode {
+            PwmMode::TaTobj1 => false,
+            PwmMode::TaTobj2 | PwmMode::Tobj2 | PwmMode::Tobj1Tobj2 => true,
+        }
+    }
+
+    /// Theoretical conversion time in milliseconds
+    ///
+    /// This is the time until a step change in the object temperature is
+    /// reflected at 99% in the readings. It is computed as the FIR refresh
+    /// period (depending on the single/dual IR sensor setting) times the number
+    /// of samples the IIR filter needs to settle.
+    pub fn conversion_time_ms(&self) -> f32 {
+        self.fir.refresh_period_ms(self.dual_ir_sensor) * f32::from(self.iir.settling_samples())
+    }
+
+    /// FIR and IIR filter contributions to the conversion time in milliseconds
+    ///
+    /// The FIR stage contributes one refresh period and the IIR stage the
+    /// remaining refresh periods needed for the IIR filter to settle.
+    /// The sum of both is `conversion_time_ms()`.
+    pub fn timing_breakdown(&self) -> (f32, f32) {
+        let period = self.fir.refresh_period_ms(self.dual_ir_sensor);
+        let iir_samples = self.iir.settling_samples() - 1;
+        (period, period * f32::from(iir_samples))
+    }
+
     fn from_bits(bits: u16) -> Self {
         let iir = match bits & 0b111 {
             0b000 => Iir::Step50,
@@ -336,3 +2216,320 @@ impl Config {
         bits
     }
 }
+
+/// Confidence in a reading
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    /// The reading can be trusted
+    High,
+    /// The raw IR data is close to saturation
+    Medium,
+    /// The reading is flagged as erroneous or the raw IR data is saturated
+    Low,
+}
+
+/// Classified object temperature reading
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reading {
+    /// The reading can be used
+    Valid(Temperature),
+    /// The reading is flagged as erroneous by the device
+    Flagged(Temperature),
+    /// The raw IR data is saturated
+    Saturated,
+}
+
+/// Device flags
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Flags {
+    /// The previous write/erase EEPROM access is still in progress
+    pub eeprom_busy: bool,
+    /// EEPROM double error has occurred
+    pub eeprom_dead: bool,
+    /// POR initialization routine is still ongoing
+    pub init_ongoing: bool,
+}
+
+impl Flags {
+    fn from_bits(bits: u16) -> Self {
+        Flags {
+            eeprom_busy: (bits & (1 << 7)) != 0,
+            eeprom_dead: (bits & (1 << 5)) != 0,
+            // INIT is low active
+            init_ongoing: (bits & (1 << 4)) == 0,
+        }
+    }
+}
+
+/// Contents of all RAM and EEPROM registers
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceState {
+    /// RAM registers (commands 0x00-0x1F)
+    pub ram: [u16; 32],
+    /// EEPROM registers (commands 0x20-0x3F)
+    pub eeprom: [u16; 32],
+}
+
+impl DeviceState {
+    /// Response frame of a register read as sent by the device
+    ///
+    /// This returns the LSB, MSB and PEC bytes the device at `address` would
+    /// send when reading `register`, e.g. to set up I²C mock transactions
+    /// replaying a captured state.
+    /// Returns `None` for registers outside of the captured RAM and EEPROM ranges.
+    pub fn frame(&self, address: u8, register: u8) -> Option<[u8; 3]> {
+        let word = match register {
+            0x00..=0x1F => self.ram[usize::from(register)],
+            0x20..=0x3F => self.eeprom[usize::from(register - Register::TOMAX)],
+            _ => return None,
+        };
+        let [lsb, msb] = word.to_le_bytes();
+        let pec = crc8(&[address << 1, register, (address << 1) | 1, lsb, msb]);
+        Some([lsb, msb, pec])
+    }
+}
+
+#[cfg(feature = "bitflags")]
+bitflags::bitflags! {
+    /// Device flags as bit flags
+    ///
+    /// The flags register has no separate POR flag. The POR initialization
+    /// is reported through `INIT`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct FlagBits: u16 {
+        /// The previous write/erase EEPROM access is still in progress
+        const EE_BUSY = 1 << 7;
+        /// EEPROM double error has occurred
+        const EE_DEAD = 1 << 5;
+        /// POR initialization routine is finished (the INIT flag is low active)
+        const INIT = 1 << 4;
+    }
+}
+
+/// Full device status report
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StatusReport {
+    /// Device ID
+    pub device_id: u64,
+    /// Configuration register 1
+    pub config: Config,
+    /// Emissivity epsilon
+    pub emissivity: f32,
+    /// Ambient temperature
+    pub ambient: Temperature,
+    /// Object 1 temperature
+    pub object1: Temperature,
+    /// Object 2 temperature (only for dual IR sensor configurations)
+    pub object2: Option<Temperature>,
+    /// Device flags
+    pub flags: Flags,
+}
+
+/// Contents of all configuration EEPROM registers
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeviceConfig {
+    /// Configuration register 1
+    pub config_1: Config,
+    /// Emissivity epsilon
+    pub emissivity: f32,
+    /// Raw PWM control register
+    pub pwmctrl: u16,
+    /// Minimum object temperature of the PWM output range (TOMIN)
+    pub tomin: Temperature,
+    /// Maximum object temperature of the PWM output range (TOMAX)
+    pub tomax: Temperature,
+    /// Slave address (least significant byte of the address register)
+    pub address: u8,
+}
+
+#[cfg(test)]
+mod config_tests {
+    use super::{recommend_filters, Config, Fir, Gain, Iir, IrTopology, PwmMode};
+
+    fn config_with_pwm_mode(pwm_mode: PwmMode) -> Config {
+        Config {
+            pwm_mode,
+            ..Config::from_bits(0)
+        }
+    }
+
+    #[test]
+    fn pwm_includes_object2() {
+        assert!(!config_with_pwm_mode(PwmMode::TaTobj1).pwm_includes_object2());
+        assert!(config_with_pwm_mode(PwmMode::TaTobj2).pwm_includes_object2());
+        assert!(config_with_pwm_mode(PwmMode::Tobj2).pwm_includes_object2());
+        assert!(config_with_pwm_mode(PwmMode::Tobj1Tobj2).pwm_includes_object2());
+    }
+
+    fn config_with_filters(fir: Fir, iir: Iir, dual_ir_sensor: bool) -> Config {
+        Config {
+            fir,
+            iir,
+            dual_ir_sensor,
+            ..Config::from_bits(0)
+        }
+    }
+
+    #[test]
+    fn conversion_time_without_iir() {
+        let config = config_with_filters(Fir::Step1024, Iir::Step100, false);
+        assert!((config.conversion_time_ms() - 93.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn conversion_time_single_sensor() {
+        let config = config_with_filters(Fir::Step128, Iir::Step50, false);
+        assert!((config.conversion_time_ms() - 252.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn conversion_time_dual_sensor() {
+        let config = config_with_filters(Fir::Step1024, Iir::Step13, true);
+        assert!((config.conversion_time_ms() - 4830.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn timing_breakdown_without_iir() {
+        let config = config_with_filters(Fir::Step1024, Iir::Step100, false);
+        let (fir, iir) = config.timing_breakdown();
+        assert!((fir - 93.0).abs() < 0.001);
+        assert!(iir.abs() < 0.001);
+    }
+
+    #[test]
+    fn timing_breakdown_with_iir() {
+        let config = config_with_filters(Fir::Step128, Iir::Step50, false);
+        let (fir, iir) = config.timing_breakdown();
+        assert!((fir - 36.0).abs() < 0.001);
+        assert!((iir - 216.0).abs() < 0.001);
+        assert!((fir + iir - config.conversion_time_ms()).abs() < 0.001);
+    }
+
+    #[test]
+    fn recommend_filters_for_loose_target() {
+        assert_eq!((Fir::Step128, Iir::Step100), recommend_filters(1.0));
+    }
+
+    #[test]
+    fn recommend_filters_for_medium_target() {
+        assert_eq!((Fir::Step256, Iir::Step100), recommend_filters(0.1));
+    }
+
+    #[test]
+    fn recommend_filters_for_tight_target() {
+        assert_eq!((Fir::Step1024, Iir::Step50), recommend_filters(0.03));
+    }
+
+    #[test]
+    fn recommend_filters_for_unreachable_target() {
+        assert_eq!((Fir::Step1024, Iir::Step13), recommend_filters(0.001));
+    }
+
+    #[test]
+    fn describe_default() {
+        assert_eq!(
+            [
+                ("IIR", "100%"),
+                ("Repeat sensor selftest", "Off"),
+                ("PWM mode", "Tobj1, Tobj2"),
+                ("IR sensor", "Single"),
+                ("Ks sign", "Negative"),
+                ("FIR", "1024"),
+                ("Gain", "12.5"),
+                ("Kt2 sign", "Positive"),
+                ("Sensor selftest", "Off"),
+            ],
+            Config::default().describe()
+        );
+    }
+
+    #[test]
+    fn diff_lists_differing_fields() {
+        let spec = Config::default();
+        let programmed = Config {
+            fir: Fir::Step128,
+            dual_ir_sensor: !spec.dual_ir_sensor,
+            ..spec
+        };
+        assert_eq!(&["IR sensor", "FIR"], programmed.diff(&spec).as_slice());
+        assert!(spec.diff(&spec).is_empty());
+    }
+
+    #[test]
+    fn non_default_settings_lists_changed_fields() {
+        let config = Config {
+            iir: Iir::Step50,
+            gain: Gain::Gain1,
+            ..Config::default()
+        };
+        assert_eq!(&["IIR", "Gain"], config.non_default_settings().as_slice());
+        assert!(Config::default().non_default_settings().is_empty());
+    }
+
+    #[test]
+    fn profile_id_is_stable() {
+        let config = Config::default();
+        assert_eq!(207, config.profile_id());
+        let dual = Config {
+            dual_ir_sensor: true,
+            ..config
+        };
+        assert_eq!(148, dual.profile_id());
+    }
+
+    #[test]
+    fn coefficients_positive_only_without_negative_signs() {
+        let mut config = config_with_filters(Fir::Step128, Iir::Step100, false);
+        for (ks, kt2, expected) in [
+            (false, false, true),
+            (true, false, false),
+            (false, true, false),
+            (true, true, false),
+        ] {
+            config.ks_sign_negative = ks;
+            config.kt2_sign_negative = kt2;
+            assert_eq!(expected, config.coefficients_positive());
+        }
+    }
+
+    #[test]
+    fn selftest_enabled_inverts_disabled_bit() {
+        let mut config = config_with_filters(Fir::Step128, Iir::Step100, false);
+        assert!(config.selftest_enabled());
+        config.sensor_selftest_disabled = true;
+        assert!(!config.selftest_enabled());
+    }
+
+    #[test]
+    fn ir_topology_maps_dual_ir_sensor() {
+        let mut config = config_with_filters(Fir::Step128, Iir::Step100, false);
+        assert_eq!(IrTopology::Single, config.ir_topology());
+        config.dual_ir_sensor = true;
+        assert_eq!(IrTopology::Dual, config.ir_topology());
+        config.set_ir_topology(IrTopology::Single);
+        assert!(!config.dual_ir_sensor);
+        config.set_ir_topology(IrTopology::Dual);
+        assert!(config.dual_ir_sensor);
+    }
+
+    #[test]
+    fn all_bit_patterns_round_trip() {
+        for bits in 0..=u16::MAX {
+            assert_eq!(bits, Config::from_bits(bits).as_bits());
+        }
+    }
+
+    #[test]
+    fn reserved_mask_matches_mapped_bits() {
+        assert_eq!(
+            !Config::from_bits(u16::MAX).as_bits(),
+            Config::RESERVED_MASK
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     ic,
     register_access::mlx90615::{self, Register, DEV_ADDR},
-    Error, Mlx9061x, SlaveAddr, Temperature,
+    Error, Mlx9061x, SlaveAddr, Temperature, DEFAULT_FILTER_WINDOW,
 };
 use core::marker::PhantomData;
 use embedded_hal::{delay::DelayNs, digital::OutputPin, i2c::I2c};
@@ -29,6 +29,20 @@ where
             i2c,
             eeprom_write_delay_ms,
             address,
+            sample_index: 0,
+            device_id: None,
+            last_object1: None,
+            peak_object1: None,
+            thermostat_on: false,
+            settling_time_ms: 0,
+            eeprom_write_protected: false,
+            ta_range: None,
+            emissivity: None,
+            ir_scale: None,
+            calibration: (1.0, 0.0),
+            filter_window: DEFAULT_FILTER_WINDOW,
+            filter_samples: heapless::Deque::new(),
+            ema_object1: None,
             _ic: PhantomData,
         })
     }
@@ -50,7 +64,18 @@ where
         Ok(Temperature(t))
     }
 
+    /// Read the object 2 temperature
+    ///
+    /// The MLX90615 only has a single object temperature register, so this
+    /// always returns `Error::UnsupportedOperation` without communicating with
+    /// the device. It is provided for code generic over both devices.
+    pub fn object2_temperature(&mut self) -> Result<Temperature, Error<E>> {
+        Err(Error::UnsupportedOperation)
+    }
+
     /// Read the raw IR data
+    ///
+    /// The raw IR data is stored in sign-magnitude format, bit 15 being the sign.
     pub fn raw_ir(&mut self) -> Result<i16, Error<E>> {
         self.read_i16(Register::RAW_IR)
     }
@@ -69,19 +94,53 @@ where
         epsilon: f32,
         delay: &mut D,
     ) -> Result<(), Error<E>> {
+        let eps = Self::emissivity_to_raw(epsilon)?;
+        let result = self.write_u16_eeprom(Register::EMISSIVITY, eps, delay);
+        self.update_cached_emissivity(eps, result)
+    }
+
+    pub(crate) fn emissivity_to_raw(epsilon: f32) -> Result<u16, Error<E>> {
         if epsilon < 0.0 || epsilon > 1.0 {
             return Err(Error::InvalidInputData);
         }
-        let eps = (epsilon * 16384.0 + 0.5) as u16;
-        self.write_u16_eeprom(Register::EMISSIVITY, eps, delay)
+        Ok((epsilon * 16384.0 + 0.5) as u16)
     }
 
     /// Get the device ID
+    ///
+    /// The first ID word is the most significant one. See `device_id_be()`.
+    ///
+    /// The ID is only read from the device on the first call and cached afterwards.
+    /// Use `refresh_device_id()` to read it again.
     pub fn device_id(&mut self) -> Result<u32, Error<E>> {
+        match self.device_id {
+            Some(id) => Ok(id as u32),
+            None => self.refresh_device_id(),
+        }
+    }
+
+    /// Read the device ID from the device and update the cached value
+    pub fn refresh_device_id(&mut self) -> Result<u32, Error<E>> {
+        let id = self.device_id_be()?;
+        self.device_id = Some(u64::from(id));
+        Ok(id)
+    }
+
+    /// Get the device ID assembled with the first ID word as the most significant one
+    pub fn device_id_be(&mut self) -> Result<u32, Error<E>> {
         let id0 = self.read_u16(Register::ID0)?;
         let id1 = self.read_u16(Register::ID0 + 1)?;
         Ok((u32::from(id0) << 16) | u32::from(id1))
     }
+
+    /// Get the device ID assembled with the first ID word as the least significant one
+    ///
+    /// Some clone devices report the ID words in reverse order.
+    pub fn device_id_le(&mut self) -> Result<u32, Error<E>> {
+        let id0 = self.read_u16(Register::ID0)?;
+        let id1 = self.read_u16(Register::ID0 + 1)?;
+        Ok((u32::from(id1) << 16) | u32::from(id0))
+    }
 }
 
 /// Wake device from sleep mode.
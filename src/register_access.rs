@@ -2,6 +2,28 @@ use crate::{Error, Mlx9061x, SlaveAddr};
 use embedded_hal::{delay::DelayNs, i2c::I2c};
 use smbus_pec::pec;
 
+/// Compute the SMBus packet error code (CRC-8, polynomial 0x07) of some data
+///
+/// This can be evaluated in const context, e.g. to build static frame tables.
+pub const fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    let mut i = 0;
+    while i < data.len() {
+        crc ^= data[i];
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
 fn msb_lsb_to_sign_magnitude(value: u16) -> i16 {
     let sign_bit = value & 0b1000_0000_0000_0000;
 
@@ -19,6 +41,8 @@ pub mod mlx90614 {
     pub const SLEEP_COMMAND: u8 = 0xFF;
     pub const WAKE_DELAY_MS: u8 = 33;
     pub const DEV_ADDR: u8 = 0x5A;
+    pub const AMBIENT_MIN_MILLICELSIUS: i32 = -40_000;
+    pub const AMBIENT_MAX_MILLICELSIUS: i32 = 125_000;
 
     pub struct Register {}
 
@@ -28,10 +52,15 @@ pub mod mlx90614 {
         pub const TA: u8 = 0x06;
         pub const TOBJ1: u8 = 0x07;
         pub const TOBJ2: u8 = 0x08;
+        pub const TOMAX: u8 = /*0x00 |*/ EEPROM_COMMAND;
+        pub const TOMIN: u8 = 0x01 | EEPROM_COMMAND;
+        pub const PWMCTRL: u8 = 0x02 | EEPROM_COMMAND;
+        pub const TARANGE: u8 = 0x03 | EEPROM_COMMAND;
         pub const EMISSIVITY: u8 = 0x04 | EEPROM_COMMAND;
         pub const CONFIG_1: u8 = 0x05 | EEPROM_COMMAND;
         pub const ADDRESS: u8 = 0x0E | EEPROM_COMMAND;
         pub const ID0: u8 = 0x1C | EEPROM_COMMAND;
+        pub const FLAGS: u8 = 0xF0;
     }
 }
 
@@ -41,6 +70,8 @@ pub mod mlx90615 {
     pub const SLEEP_COMMAND: u8 = 0xC6;
     pub const WAKE_DELAY_MS: u8 = 39;
     pub const DEV_ADDR: u8 = 0x5B;
+    pub const AMBIENT_MIN_MILLICELSIUS: i32 = -40_000;
+    pub const AMBIENT_MAX_MILLICELSIUS: i32 = 85_000;
 
     pub struct Register {}
 
@@ -58,11 +89,26 @@ impl<E, I2C, IC> Mlx9061x<I2C, IC>
 where
     I2C: I2c<Error = E>,
 {
+    /// Read the raw response frame of a register
+    ///
+    /// The three bytes returned by the device (LSB, MSB and PEC) are copied to
+    /// `out` without any interpretation. In particular, the PEC is not checked.
+    /// This is intended for protocol debugging.
+    pub fn read_raw_frame(&mut self, register: u8, out: &mut [u8; 3]) -> Result<(), Error<E>> {
+        self.i2c
+            .write_read(self.address, &[register], out)
+            .map_err(Error::I2C)
+    }
+
+    pub(crate) fn read_u16_unverified(&mut self, register: u8) -> Result<u16, Error<E>> {
+        let mut data = [0; 3];
+        self.read_raw_frame(register, &mut data)?;
+        Ok(u16::from(data[0]) | (u16::from(data[1]) << 8))
+    }
+
     pub(crate) fn read_u16(&mut self, register: u8) -> Result<u16, Error<E>> {
         let mut data = [0; 3];
-        self.i2c
-            .write_read(self.address, &[register], &mut data)
-            .map_err(Error::I2C)?;
+        self.read_raw_frame(register, &mut data)?;
         let pec = data[2];
         Self::check_pec(
             &[
@@ -90,6 +136,10 @@ where
     }
 
     pub(crate) fn write_u16(&mut self, command: u8, data: u16) -> Result<(), Error<E>> {
+        // all writable registers are EEPROM registers
+        if self.eeprom_write_protected {
+            return Err(Error::WriteProtected);
+        }
         let low = data as u8;
         let high = (data >> 8) as u8;
         let pec = pec(&[self.address << 1, command, low, high]);
@@ -109,6 +159,21 @@ where
         self.write_u16(command, data)
     }
 
+    pub(crate) fn write_u16_eeprom_verified<D: DelayNs>(
+        &mut self,
+        command: u8,
+        data: u16,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        self.write_u16_eeprom(command, data, delay)?;
+        delay.delay_ms(u32::from(self.eeprom_write_delay_ms));
+        if self.read_u16(command)? == data {
+            Ok(())
+        } else {
+            Err(Error::BadEepromWrite)
+        }
+    }
+
     pub(crate) fn check_pec(data: &[u8], expected: u8) -> Result<(), Error<E>> {
         if pec(data) != expected {
             Err(Error::ChecksumMismatch)
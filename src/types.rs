@@ -1,6 +1,9 @@
 /// All possible errors in this crate
+///
+/// New variants may be added in future versions without a breaking change.
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error<E> {
     /// I²C bus error
     I2C(E),
@@ -12,6 +15,24 @@ pub enum Error<E> {
     BadEepromWrite,
     /// Bad temperature reading
     BadRead(Temperature),
+    /// Temperature reading outside of the device operating range
+    OutOfRange(Temperature),
+    /// Consecutive readings did not agree within the requested tolerance
+    Unstable,
+    /// The operation is not supported by this device
+    UnsupportedOperation,
+    /// The device ID does not match the expected one
+    DeviceMismatch,
+    /// EEPROM writes are disabled in the driver
+    WriteProtected,
+    /// The expected condition was not met in time
+    Timeout,
+    /// Error setting the level of a pin
+    Pin,
+    /// The sensor selftest is disabled in the configuration
+    SelftestDisabled,
+    /// Error writing to the output
+    Write,
 }
 
 /// IC marker
@@ -39,12 +60,85 @@ impl Default for SlaveAddr {
     }
 }
 
+/// Value read from the device with a verified PEC
+///
+/// This can only be created by the driver, so requiring it guarantees
+/// that the value passed the PEC check.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Verified<T>(pub(crate) T);
+
+impl<T> Verified<T> {
+    /// Get the verified value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> core::ops::Deref for Verified<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Typical emissivity of common materials
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Material {
+    /// Human skin (0.98)
+    HumanSkin,
+    /// Water (0.96)
+    Water,
+    /// Black paint (0.95)
+    BlackPaint,
+    /// Glass (0.92)
+    Glass,
+    /// Wood (0.90)
+    Wood,
+    /// Oxidized steel (0.80)
+    OxidizedSteel,
+    /// Polished aluminum (0.05)
+    ///
+    /// Note that this is below the minimum emissivity supported by the MLX90614.
+    PolishedAluminum,
+}
+
+impl Material {
+    /// Typical emissivity epsilon of the material
+    pub fn emissivity(&self) -> f32 {
+        match self {
+            Material::HumanSkin => 0.98,
+            Material::Water => 0.96,
+            Material::BlackPaint => 0.95,
+            Material::Glass => 0.92,
+            Material::Wood => 0.90,
+            Material::OxidizedSteel => 0.80,
+            Material::PolishedAluminum => 0.05,
+        }
+    }
+}
+
 /// Temperature value
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Temperature(pub(crate) u16);
 
 impl Temperature {
+    /// Create a temperature from a raw value in 0.02K units
+    pub fn from_raw(raw: u16) -> Self {
+        Temperature(raw)
+    }
+
+    /// Create a temperature from a value in celsius
+    ///
+    /// The value is rounded to the nearest 0.02K step and saturated to the
+    /// representable range.
+    pub fn from_celsius(celsius: f32) -> Self {
+        Temperature(((celsius + 273.15) / 0.02 + 0.5) as u16)
+    }
+
     /// Raw temperature value
     pub fn raw(&self) -> u16 {
         self.0
@@ -81,6 +175,48 @@ impl Temperature {
     }
 }
 
+/// Unit a temperature can be converted to
+///
+/// This allows selecting the unit of a reading through a type parameter.
+/// See `Celsius`, `Fahrenheit` and `Kelvin`.
+pub trait TemperatureUnit {
+    /// Convert a temperature into this unit
+    fn from_temperature(temperature: Temperature) -> Self;
+}
+
+/// Temperature in celsius
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Celsius(pub f32);
+
+/// Temperature in fahrenheit
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Fahrenheit(pub f32);
+
+/// Temperature in kelvin
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Kelvin(pub f32);
+
+impl TemperatureUnit for Celsius {
+    fn from_temperature(temperature: Temperature) -> Self {
+        Celsius(temperature.celsius())
+    }
+}
+
+impl TemperatureUnit for Fahrenheit {
+    fn from_temperature(temperature: Temperature) -> Self {
+        Fahrenheit(temperature.fahrenheit())
+    }
+}
+
+impl TemperatureUnit for Kelvin {
+    fn from_temperature(temperature: Temperature) -> Self {
+        Kelvin(temperature.kelvin())
+    }
+}
+
 #[cfg(test)]
 mod temperature_tests {
     use super::Temperature;
@@ -97,6 +233,13 @@ mod temperature_tests {
         assert_eq!(temp.millifahrenheit(), 32018);
     }
 
+    #[test]
+    fn from_celsius() {
+        assert_eq!(Temperature(13658), Temperature::from_celsius(0.01));
+        assert_eq!(Temperature(18658), Temperature::from_celsius(100.01));
+        assert_eq!(Temperature(0), Temperature::from_celsius(-300.0));
+    }
+
     #[test]
     fn zero() {
         let temp = Temperature(0);
@@ -0,0 +1,29 @@
+#![cfg(feature = "async")]
+
+use embassy_futures::block_on;
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+use mlx9061x::asynch::scan;
+
+#[test]
+fn can_scan() {
+    let mut i2c = I2cMock::new(&[
+        I2cTrans::write(0x5A, vec![]),
+        I2cTrans::write(0x5B, vec![])
+            .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+        I2cTrans::write(0x5C, vec![]),
+    ]);
+    let found = block_on(scan(&mut i2c, 0x5A..=0x5C));
+    assert_eq!(&[0x5A, 0x5C], found.as_slice());
+    i2c.done();
+}
+
+#[test]
+fn scan_skips_reserved_addresses() {
+    let mut i2c = I2cMock::new(&[I2cTrans::write(0x08, vec![]), I2cTrans::write(0x77, vec![])]);
+    let found = block_on(scan(&mut i2c, 0..=0x08));
+    assert_eq!(&[0x08], found.as_slice());
+    let found = block_on(scan(&mut i2c, 0x77..=0xFF));
+    assert_eq!(&[0x77], found.as_slice());
+    i2c.done();
+}
@@ -13,10 +13,15 @@ pub mod mlx90614 {
         pub const TA: u8 = 0x06;
         pub const TOBJ1: u8 = 0x07;
         pub const TOBJ2: u8 = 0x08;
+        pub const TOMAX: u8 = /*0x00 |*/ EEPROM_COMMAND;
+        pub const TOMIN: u8 = 0x01 | EEPROM_COMMAND;
+        pub const PWMCTRL: u8 = 0x02 | EEPROM_COMMAND;
+        pub const TARANGE: u8 = 0x03 | EEPROM_COMMAND;
         pub const EMISSIVITY: u8 = 0x04 | EEPROM_COMMAND;
         pub const CONFIG_1: u8 = 0x05 | EEPROM_COMMAND;
         pub const ADDRESS: u8 = 0x0E | EEPROM_COMMAND;
         pub const ID0: u8 = 0x1C | EEPROM_COMMAND;
+        pub const FLAGS: u8 = 0xF0;
     }
 }
 
@@ -1,10 +1,14 @@
 mod base;
 use crate::base::{destroy, mlx90614, mlx90615, new_mlx90614, new_mlx90615};
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
 use embedded_hal_mock::eh1::{
     delay::NoopDelay,
     i2c::{Mock as I2cMock, Transaction as I2cTrans},
 };
-use mlx9061x::{Error, Mlx9061x, SlaveAddr};
+use mlx9061x::{
+    crc8, general_call_reset, recommended_i2c_timeout_ms, scan, Error, Mlx9061x, SlaveAddr,
+    MAX_SMBUS_FREQ_HZ,
+};
 
 macro_rules! tests {
     ($create:ident, $ic:ident) => {
@@ -45,6 +49,66 @@ macro_rules! tests {
             destroy(sensor);
         }
 
+        #[test]
+        fn can_read_raw_frame_without_checking_pec() {
+            let mut sensor = $create(&[I2cTrans::write_read(
+                $ic::DEV_ADDR,
+                vec![$ic::Register::TA],
+                vec![225, 57, 234],
+            )]);
+            let mut frame = [0; 3];
+            sensor
+                .read_raw_frame($ic::Register::TA, &mut frame)
+                .unwrap();
+            assert_eq!([225, 57, 234], frame);
+            destroy(sensor);
+        }
+
+        #[test]
+        fn can_abort_and_resync() {
+            // invalid PEC is ignored
+            let mut sensor = $create(&[I2cTrans::write_read(
+                $ic::DEV_ADDR,
+                vec![$ic::Register::TA],
+                vec![225, 57, 234],
+            )]);
+            sensor.abort_and_resync(&mut NoopDelay {}).unwrap();
+            destroy(sensor);
+        }
+
+        #[test]
+        fn write_protected_set_emissivity_returns_error() {
+            let mut sensor = $create(&[]);
+            sensor.set_eeprom_write_protect(true);
+            assert_error!(
+                sensor.set_emissivity(0.5, &mut NoopDelay {}),
+                WriteProtected
+            );
+            destroy(sensor);
+        }
+
+        #[test]
+        fn write_protected_check_clock_stretch_support_returns_error() {
+            let mut sensor = $create(&[]);
+            sensor.set_eeprom_write_protect(true);
+            assert_error!(
+                sensor.check_clock_stretch_support(&mut NoopDelay {}),
+                WriteProtected
+            );
+            destroy(sensor);
+        }
+
+        #[test]
+        fn write_protected_set_address_returns_error() {
+            let mut sensor = $create(&[]);
+            sensor.set_eeprom_write_protect(true);
+            assert_error!(
+                sensor.set_address(SlaveAddr::Alternative(0x5C), &mut NoopDelay {}),
+                WriteProtected
+            );
+            destroy(sensor);
+        }
+
         #[test]
         fn set_wrong_address_returns_error() {
             let mut sensor = $create(&[]);
@@ -65,6 +129,16 @@ macro_rules! tests {
             destroy(sensor);
         }
 
+        #[test]
+        fn set_emissivity_robust_zero_attempts_returns_error() {
+            let mut sensor = $create(&[]);
+            assert_error!(
+                sensor.set_emissivity_robust(0.7, &mut NoopDelay {}, 0),
+                InvalidInputData
+            );
+            destroy(sensor);
+        }
+
         #[test]
         fn set_wrong_too_big_emissivity_returns_error() {
             let mut sensor = $create(&[]);
@@ -86,3 +160,47 @@ mod mlx90615_tests {
     use super::*;
     tests!(new_mlx90615, mlx90615);
 }
+
+#[test]
+fn can_scan() {
+    let mut i2c = I2cMock::new(&[
+        I2cTrans::write(0x5A, vec![]),
+        I2cTrans::write(0x5B, vec![])
+            .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+        I2cTrans::write(0x5C, vec![]),
+    ]);
+    let found = scan(&mut i2c, 0x5A..=0x5C);
+    assert_eq!(&[0x5A, 0x5C], found.as_slice());
+    i2c.done();
+}
+
+#[test]
+fn scan_skips_reserved_addresses() {
+    let mut i2c = I2cMock::new(&[I2cTrans::write(0x08, vec![]), I2cTrans::write(0x77, vec![])]);
+    let found = scan(&mut i2c, 0..=0x08);
+    assert_eq!(&[0x08], found.as_slice());
+    let found = scan(&mut i2c, 0x77..=0xFF);
+    assert_eq!(&[0x77], found.as_slice());
+    i2c.done();
+}
+
+#[test]
+fn can_send_general_call_reset() {
+    let mut i2c = I2cMock::new(&[I2cTrans::write(0x00, vec![0x06])]);
+    general_call_reset(&mut i2c).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn can_compute_crc8_in_const_context() {
+    // ambient temperature read frame of the device at address 0x5A
+    const PEC: u8 = crc8(&[0x5A << 1, 0x06, (0x5A << 1) | 1, 0x26, 0x3A]);
+    assert_eq!(102, PEC);
+    assert_eq!(0, crc8(&[]));
+}
+
+#[test]
+fn smbus_timing_requirements() {
+    assert_eq!(100_000, MAX_SMBUS_FREQ_HZ);
+    assert_eq!(35, recommended_i2c_timeout_ms());
+}
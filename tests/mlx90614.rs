@@ -1,11 +1,18 @@
 mod base;
 use crate::base::{destroy, mlx90614, mlx90614::Register as Reg, new_mlx90614};
+use embedded_hal::i2c::ErrorKind;
 use embedded_hal_mock::eh1::{
-    delay::NoopDelay,
-    i2c::Transaction as I2cTrans,
-    pin::{Mock as PinMock, State as PinState, Transaction as PinTrans},
+    delay::{CheckedDelay, NoopDelay, Transaction as DelayTrans},
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
+};
+use mlx9061x::{
+    mlx90614::{
+        ambient_spread, configure_all, read_raw_ir_array, wake_mlx90614, Confidence, Config,
+        DeviceState, Fir, Gain, Iir, PwmMode, Reading,
+    },
+    Celsius, Error, Fahrenheit, Kelvin, Material, Mlx9061x, SlaveAddr, Temperature, Verified,
 };
-use mlx9061x::{mlx90614::wake_mlx90614, SlaveAddr};
 
 macro_rules! read_f32_test {
     ($name:ident, $method:ident, $reg:expr, $data0:expr, $data1:expr, $data2:expr, $expected:expr) => {
@@ -42,6 +49,50 @@ read_temp_test!(read_ta2, ambient_temperature, Reg::TA, 97, 58, 86, 25.75);
 read_temp_test!(read_ta3, ambient_temperature, Reg::TA, 107, 58, 212, 25.95);
 read_temp_test!(read_ta4, ambient_temperature, Reg::TA, 38, 58, 102, 24.57);
 
+#[test]
+fn can_read_validated_ambient_temperature() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::TA],
+        vec![226, 72, 134],
+    )]);
+    let t = sensor.ambient_temperature_validated().unwrap();
+    assert_near!(t.celsius(), 100.0, 0.1);
+    destroy(sensor);
+}
+
+#[test]
+fn validated_ambient_temperature_too_high_returns_error() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::TA],
+        vec![190, 78, 100],
+    )]);
+    match sensor.ambient_temperature_validated() {
+        Err(Error::OutOfRange(t)) => {
+            assert_near!(t.celsius(), 130.0, 0.1);
+        }
+        _ => panic!("Should have returned error."),
+    }
+    destroy(sensor);
+}
+
+#[test]
+fn validated_ambient_temperature_too_low_returns_error() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::TA],
+        vec![144, 44, 53],
+    )]);
+    match sensor.ambient_temperature_validated() {
+        Err(Error::OutOfRange(t)) => {
+            assert_near!(t.celsius(), -45.0, 0.1);
+        }
+        _ => panic!("Should have returned error."),
+    }
+    destroy(sensor);
+}
+
 read_temp_test!(
     read_object1_temp,
     object1_temperature,
@@ -52,6 +103,42 @@ read_temp_test!(
     24.57
 );
 
+#[test]
+fn can_read_object1_verified() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::TOBJ1],
+        vec![38, 58, 112],
+    )]);
+    let t: Verified<Temperature> = sensor.object1_temperature_verified().unwrap();
+    assert_near!(t.celsius(), 24.57, 0.1);
+    assert_eq!(0x3A26, t.into_inner().raw());
+    destroy(sensor);
+}
+
+#[test]
+fn object1_verified_crc_mismatch() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::TOBJ1],
+        vec![38, 58, 113],
+    )]);
+    assert_crc_mismatch!(sensor.object1_temperature_verified());
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_object1_unverified() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::TOBJ1],
+        vec![38, 58, 113],
+    )]);
+    let t: Temperature = sensor.object1_temperature_unverified().unwrap();
+    assert_near!(t.celsius(), 24.57, 0.1);
+    destroy(sensor);
+}
+
 read_temp_test!(
     read_object2_temp,
     object2_temperature,
@@ -62,6 +149,124 @@ read_temp_test!(
     24.57
 );
 
+#[test]
+fn can_read_object1_indexed() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![38, 58, 112]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![97, 58, 64]),
+    ]);
+    let (index, t) = sensor.object1_indexed().unwrap();
+    assert_eq!(0, index);
+    assert_near!(t.celsius(), 24.57, 0.1);
+    let (index, t) = sensor.object1_indexed().unwrap();
+    assert_eq!(1, index);
+    assert_near!(t.celsius(), 25.75, 0.1);
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_object1_if_changed() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![38, 58, 112]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![38, 58, 112]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![97, 58, 64]),
+    ]);
+    let t = sensor.object1_if_changed().unwrap().unwrap();
+    assert_near!(t.celsius(), 24.57, 0.1);
+    assert_eq!(None, sensor.object1_if_changed().unwrap());
+    let t = sensor.object1_if_changed().unwrap().unwrap();
+    assert_near!(t.celsius(), 25.75, 0.1);
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_object1_debounced() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![38, 58, 112]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![97, 58, 64]),
+    ]);
+    let t = sensor.object1_temperature_debounced(1.5).unwrap();
+    assert_near!(t.celsius(), 25.75, 0.1);
+    destroy(sensor);
+}
+
+#[test]
+fn object1_debounced_disagreeing_returns_error() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![38, 58, 112]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![97, 58, 64]),
+    ]);
+    assert_error!(sensor.object1_temperature_debounced(0.5), Unstable);
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_object1_raw_bytes() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::TOBJ1],
+        vec![38, 58, 112],
+    )]);
+    assert_eq!([0x3A, 0x26], sensor.object1_raw_bytes().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_emissivity_raw_bytes() {
+    // emissivity 0.7
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::EMISSIVITY],
+        vec![51, 179, 36],
+    )]);
+    assert_eq!([0xB3, 0x33], sensor.emissivity_raw_bytes().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn object1_if_changed_returns_reading_after_cache_reset() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![38, 58, 112]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![38, 58, 112]),
+    ]);
+    assert!(sensor.object1_if_changed().unwrap().is_some());
+    sensor.reset_cache();
+    assert!(sensor.object1_if_changed().unwrap().is_some());
+    destroy(sensor);
+}
+
+fn converging_object1_transactions() -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![66, 57, 216]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![166, 57, 207]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![191, 57, 37]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![193, 57, 81]),
+    ]
+}
+
+#[test]
+fn can_wait_for_stable_object1() {
+    let mut sensor = new_mlx90614(&converging_object1_transactions());
+    let t = sensor
+        .wait_for_stable_object1(&mut NoopDelay {}, 0.1, 10)
+        .unwrap();
+    assert_near!(t.celsius(), 22.55, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn wait_for_stable_object1_not_converging_returns_error() {
+    let mut transactions = converging_object1_transactions();
+    transactions.pop();
+    let mut sensor = new_mlx90614(&transactions);
+    assert_error!(
+        sensor.wait_for_stable_object1(&mut NoopDelay {}, 0.1, 3),
+        Unstable
+    );
+    destroy(sensor);
+}
+
 read_i16_test!(
     read_raw_ir1,
     new_mlx90614,
@@ -98,6 +303,92 @@ fn can_change_address() {
     destroy(sensor);
 }
 
+#[test]
+fn can_change_address_safely() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![mlx90614::SLEEP_COMMAND, 232]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::ADDRESS, 0, 0, 175]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::ADDRESS, 0x5C, 0, 95]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::ADDRESS], vec![0x5C, 0, 158]),
+    ]);
+    let mut scl = PinMock::new(&[PinTrans::set(PinState::High)]);
+    let mut sda = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let mut delay = CheckedDelay::new(&[
+        // wake up
+        DelayTrans::delay_ms(33),
+        DelayTrans::delay_ms(5),
+        DelayTrans::delay_ms(5),
+    ]);
+    sensor
+        .set_address_safe(SlaveAddr::Alternative(0x5C), &mut scl, &mut sda, &mut delay)
+        .unwrap();
+    delay.done();
+    scl.done();
+    sda.done();
+    destroy(sensor);
+}
+
+#[test]
+fn write_protected_set_address_safe_does_not_sleep() {
+    let mut sensor = new_mlx90614(&[]);
+    sensor.set_eeprom_write_protect(true);
+    let mut scl = PinMock::new(&[]);
+    let mut sda = PinMock::new(&[]);
+    assert_error!(
+        sensor.set_address_safe(
+            SlaveAddr::Alternative(0x5C),
+            &mut scl,
+            &mut sda,
+            &mut NoopDelay {}
+        ),
+        WriteProtected
+    );
+    scl.done();
+    sda.done();
+    destroy(sensor);
+}
+
+read_i16_test!(
+    read_raw_ir1_negative_max,
+    new_mlx90614,
+    mlx90614::DEV_ADDR,
+    raw_ir_channel1,
+    Reg::RAW_IR1,
+    0xFF,
+    0xFF,
+    24,
+    -32767
+);
+
+read_i16_test!(
+    read_raw_ir2_negative_one,
+    new_mlx90614,
+    mlx90614::DEV_ADDR,
+    raw_ir_channel2,
+    Reg::RAW_IR2,
+    0x01,
+    0x80,
+    182,
+    -1
+);
+
+#[test]
+fn write_pec_includes_address() {
+    // The PEC of a write frame covers the address write byte, so the same
+    // command and data produce a different PEC on another address.
+    let mut sensor = Mlx9061x::new_mlx90614(
+        I2cMock::new(&[
+            I2cTrans::write(0x5C, vec![Reg::EMISSIVITY, 0, 0, 192]),
+            I2cTrans::write(0x5C, vec![Reg::EMISSIVITY, 51, 179, 22]),
+        ]),
+        SlaveAddr::Alternative(0x5C),
+        5,
+    )
+    .unwrap();
+    sensor.set_emissivity(0.7, &mut NoopDelay {}).unwrap();
+    destroy(sensor);
+}
+
 #[test]
 fn can_set_emissivity() {
     let mut sensor = new_mlx90614(&[
@@ -108,6 +399,189 @@ fn can_set_emissivity() {
     destroy(sensor);
 }
 
+#[test]
+fn can_set_emissivity_10_percent() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 0, 40]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 154, 25, 4]),
+    ]);
+    sensor
+        .set_emissivity_percent(10, &mut NoopDelay {})
+        .unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn can_set_emissivity_95_percent() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 0, 40]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 50, 243, 44]),
+    ]);
+    sensor
+        .set_emissivity_percent(95, &mut NoopDelay {})
+        .unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn set_wrong_emissivity_percent_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(
+        sensor.set_emissivity_percent(5, &mut NoopDelay {}),
+        InvalidInputData
+    );
+    destroy(sensor);
+}
+
+fn emissivity_write_transactions(read_back: Vec<u8>) -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 0, 40]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 51, 179, 254]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY], read_back),
+    ]
+}
+
+#[test]
+fn clock_stretch_is_supported_when_write_verifies() {
+    let mut transactions = vec![I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::EMISSIVITY],
+        vec![51, 179, 36],
+    )];
+    transactions.extend(emissivity_write_transactions(vec![51, 179, 36]));
+    let mut sensor = new_mlx90614(&transactions);
+    assert!(sensor
+        .check_clock_stretch_support(&mut NoopDelay {})
+        .unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn clock_stretch_is_not_supported_when_writes_keep_failing() {
+    let mut transactions = vec![I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::EMISSIVITY],
+        vec![51, 179, 36],
+    )];
+    for _ in 0..3 {
+        transactions.extend(emissivity_write_transactions(vec![0, 0, 242]));
+    }
+    // best-effort restore
+    transactions.extend([
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 0, 40]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 51, 179, 254]),
+    ]);
+    let mut sensor = new_mlx90614(&transactions);
+    assert!(!sensor
+        .check_clock_stretch_support(&mut NoopDelay {})
+        .unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_factory_program() {
+    let mut transactions = emissivity_write_transactions(vec![51, 179, 36]);
+    transactions.extend([
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 0, 0, 67]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 180, 159, 140]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::ADDRESS, 0, 0, 175]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::ADDRESS, 0x5B, 0, 52]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::ADDRESS], vec![0x5B, 0, 245]),
+    ]);
+    let mut sensor = new_mlx90614(&transactions);
+    sensor
+        .factory_program(
+            SlaveAddr::Alternative(0x5B),
+            0.7,
+            Config::default(),
+            &mut NoopDelay {},
+        )
+        .unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn factory_program_stops_at_first_mismatch() {
+    let mut sensor = new_mlx90614(&emissivity_write_transactions(vec![0, 0, 242]));
+    assert_error!(
+        sensor.factory_program(
+            SlaveAddr::Alternative(0x5B),
+            0.7,
+            Config::default(),
+            &mut NoopDelay {},
+        ),
+        BadEepromWrite
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn can_set_emissivity_robust_with_retry() {
+    let mut transactions = emissivity_write_transactions(vec![0, 0, 242]);
+    transactions.extend(emissivity_write_transactions(vec![51, 179, 36]));
+    let mut sensor = new_mlx90614(&transactions);
+    sensor
+        .set_emissivity_robust(0.7, &mut NoopDelay {}, 3)
+        .unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn can_set_emissivity_verified_independent() {
+    let mut sensor = new_mlx90614(&emissivity_write_transactions(vec![51, 179, 36]));
+    let mut delay = CheckedDelay::new(&[DelayTrans::delay_ms(5), DelayTrans::delay_ms(5)]);
+    sensor
+        .set_emissivity_verified_independent(0.7, &mut delay)
+        .unwrap();
+    delay.done();
+    destroy(sensor);
+}
+
+#[test]
+fn set_emissivity_verified_independent_mismatch_returns_error() {
+    let mut sensor = new_mlx90614(&emissivity_write_transactions(vec![0, 0, 242]));
+    assert_error!(
+        sensor.set_emissivity_verified_independent(0.7, &mut NoopDelay {}),
+        BadEepromWrite
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn set_emissivity_robust_exhausted_returns_error() {
+    let mut transactions = emissivity_write_transactions(vec![0, 0, 242]);
+    transactions.extend(emissivity_write_transactions(vec![0, 0, 242]));
+    let mut sensor = new_mlx90614(&transactions);
+    assert_error!(
+        sensor.set_emissivity_robust(0.7, &mut NoopDelay {}, 2),
+        BadEepromWrite
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn can_set_emissivity_material() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 0, 40]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 224, 250, 131]),
+    ]);
+    sensor
+        .set_emissivity_material(Material::HumanSkin, &mut NoopDelay {})
+        .unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn set_unsupported_emissivity_material_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(
+        sensor.set_emissivity_material(Material::PolishedAluminum, &mut NoopDelay {}),
+        InvalidInputData
+    );
+    destroy(sensor);
+}
+
 #[test]
 fn can_set_config_1() {
     let mut sensor = new_mlx90614(&[
@@ -127,43 +601,1724 @@ fn can_set_config_1() {
     destroy(sensor);
 }
 
-read_f32_test!(read_emiss, emissivity, Reg::EMISSIVITY, 51, 179, 36, 0.7);
+#[test]
+fn can_set_config_1_unchecked() {
+    // no verification read
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 0, 0, 67]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 180, 159, 140]),
+    ]);
+    sensor
+        .set_config_1_unchecked(Config::default(), &mut NoopDelay {})
+        .unwrap();
+    destroy(sensor);
+}
 
 #[test]
-fn can_get_id() {
+fn write_protection_can_be_disabled() {
     let mut sensor = new_mlx90614(&[
-        I2cTrans::write_read(
-            mlx90614::DEV_ADDR,
-            vec![mlx90614::Register::ID0],
-            vec![0x34, 0x12, 246],
-        ),
-        I2cTrans::write_read(
-            mlx90614::DEV_ADDR,
-            vec![mlx90614::Register::ID0 + 1],
-            vec![0x78, 0x56, 156],
-        ),
-        I2cTrans::write_read(
-            mlx90614::DEV_ADDR,
-            vec![mlx90614::Register::ID0 + 2],
-            vec![0xBC, 0x9A, 117],
-        ),
-        I2cTrans::write_read(
-            mlx90614::DEV_ADDR,
-            vec![mlx90614::Register::ID0 + 3],
-            vec![0xF0, 0xDE, 31],
-        ),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 0, 0, 67]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 180, 159, 140]),
     ]);
-    assert_eq!(0x1234_5678_9ABC_DEF0, sensor.device_id().unwrap());
+    sensor.set_eeprom_write_protect(true);
+    assert_error!(
+        sensor.set_config_1_unchecked(Config::default(), &mut NoopDelay {}),
+        WriteProtected
+    );
+    sensor.set_eeprom_write_protect(false);
+    sensor
+        .set_config_1_unchecked(Config::default(), &mut NoopDelay {})
+        .unwrap();
     destroy(sensor);
 }
 
 #[test]
-fn can_sleep() {
-    let mut sensor = new_mlx90614(&[I2cTrans::write(
+fn can_modify_config_1() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 0, 0, 67]),
+        // dual IR sensor
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 244, 159, 215]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![244, 159, 112]),
+    ]);
+    sensor
+        .modify_config_1(&mut NoopDelay {}, |config| config.dual_ir_sensor = true)
+        .unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn can_assert_selftest_enabled() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
         mlx90614::DEV_ADDR,
-        vec![mlx90614::SLEEP_COMMAND, 232],
+        vec![Reg::CONFIG_1],
+        vec![180, 31, 162],
     )]);
-    sensor.sleep().unwrap();
+    sensor.assert_selftest_enabled().unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn assert_selftest_enabled_when_disabled_returns_error() {
+    // default configuration has the selftest disabled
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::CONFIG_1],
+        vec![180, 159, 43],
+    )]);
+    assert_error!(sensor.assert_selftest_enabled(), SelftestDisabled);
+    destroy(sensor);
+}
+
+#[test]
+fn would_not_change_identical_config() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::CONFIG_1],
+        vec![180, 159, 43],
+    )]);
+    assert!(!sensor.would_change_config(&Config::default()).unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn would_change_differing_config() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::CONFIG_1],
+        vec![180, 159, 43],
+    )]);
+    let config = Config {
+        dual_ir_sensor: true,
+        ..Config::default()
+    };
+    assert!(sensor.would_change_config(&config).unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_apply_signal_chain() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 0, 0, 67]),
+        // gain 1, FIR 128, IIR 50%
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 176, 132, 153]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![176, 132, 62]),
+    ]);
+    let config = sensor
+        .apply_signal_chain(Gain::Gain1, Fir::Step128, Iir::Step50, &mut NoopDelay {})
+        .unwrap();
+    let expected = Config {
+        gain: Gain::Gain1,
+        fir: Fir::Step128,
+        iir: Iir::Step50,
+        ..Config::default()
+    };
+    assert_eq!(expected, config);
+    destroy(sensor);
+}
+
+#[test]
+fn write_protected_apply_signal_chain_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    sensor.set_eeprom_write_protect(true);
+    assert_error!(
+        sensor.apply_signal_chain(Gain::Gain1, Fir::Step128, Iir::Step50, &mut NoopDelay {}),
+        WriteProtected
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_repeat_selftest_enabled() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![188, 159, 131]),
+    ]);
+    assert!(!sensor.repeat_selftest_enabled().unwrap());
+    assert!(sensor.repeat_selftest_enabled().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_set_repeat_selftest() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 0, 0, 67]),
+        // only bit 3 set in addition to the default configuration
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 188, 159, 36]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![188, 159, 131]),
+    ]);
+    sensor.set_repeat_selftest(true, &mut NoopDelay {}).unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn can_set_pwm_mode() {
+    let mut sensor = new_mlx90614(&[
+        // PWM mode Tobj1, Tobj2
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 0, 0, 67]),
+        // PWM mode Ta, Tobj1, other bits untouched
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 132, 159, 117]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![132, 159, 210]),
+    ]);
+    sensor
+        .set_pwm_mode(PwmMode::TaTobj1, &mut NoopDelay {})
+        .unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn can_set_config_and_read() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 0, 0, 67]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 4, 4, 11]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![4, 4, 172]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+    ]);
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::delay_ms(5),
+        DelayTrans::delay_ms(5),
+        // FIR 128 single sensor refresh period, IIR 100%
+        DelayTrans::delay_ms(36),
+    ]);
+    let config = Config {
+        iir: Iir::Step100,
+        repeat_sensor_selftest: false,
+        pwm_mode: PwmMode::TaTobj1,
+        dual_ir_sensor: false,
+        ks_sign_negative: false,
+        fir: Fir::Step128,
+        gain: Gain::Gain1,
+        kt2_sign_negative: false,
+        sensor_selftest_disabled: false,
+    };
+    let t = sensor.set_config_and_read(config, &mut delay).unwrap();
+    assert_near!(t.celsius(), 28.93, 0.01);
+    delay.done();
+    destroy(sensor);
+}
+
+#[test]
+fn can_set_highest_accuracy() {
+    let mut sensor = new_mlx90614(&[
+        // FIR 128, IIR 100%
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 156, 34]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 0, 0, 67]),
+        // FIR 1024, IIR 13%
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 179, 159, 231]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![179, 159, 64]),
+    ]);
+    assert_eq!(0, sensor.settling_time_ms());
+    sensor.set_highest_accuracy(&mut NoopDelay {}).unwrap();
+    // 93ms single sensor refresh period * 35 IIR settling samples
+    assert_eq!(3255, sensor.settling_time_ms());
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_device_config() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write_read(
+            mlx90614::DEV_ADDR,
+            vec![Reg::EMISSIVITY],
+            vec![255, 255, 214],
+        ),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::PWMCTRL], vec![1, 2, 157]),
+        // -20°C
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOMIN], vec![227, 98, 233]),
+        // 120°C
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOMAX], vec![147, 153, 178]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::ADDRESS], vec![90, 190, 211]),
+    ]);
+    let config = sensor.read_device_config().unwrap();
+    assert_eq!(Config::default(), config.config_1);
+    assert_near!(config.emissivity, 1.0, 0.01);
+    assert_eq!(0x0201, config.pwmctrl);
+    assert_near!(config.tomin.celsius(), -20.0, 0.02);
+    assert_near!(config.tomax.celsius(), 120.0, 0.02);
+    assert_eq!(0x5A, config.address);
+    destroy(sensor);
+}
+
+#[test]
+fn can_set_low_power_mode() {
+    let mut sensor = new_mlx90614(&[
+        // FIR 1024, IIR 100%
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 0, 0, 67]),
+        // FIR 128, IIR 100%
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 180, 156, 133]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 156, 34]),
+    ]);
+    sensor.set_low_power_mode(&mut NoopDelay {}).unwrap();
+    assert_eq!(36, sensor.settling_time_ms());
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_config_1_checked() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::CONFIG_1],
+        vec![180, 159, 43],
+    )]);
+    let (config, lossy) = sensor.config_1_checked().unwrap();
+    assert_eq!(Config::default(), config);
+    assert!(!lossy);
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_config_1_reserved_bits() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::CONFIG_1],
+        vec![180, 159, 43],
+    )]);
+    assert_eq!(0, sensor.config_1_reserved_bits().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_configure_all() {
+    let mut i2c = I2cMock::new(&[
+        I2cTrans::write(0x5A, vec![Reg::CONFIG_1, 0, 0, 67]),
+        I2cTrans::write(0x5A, vec![Reg::CONFIG_1, 180, 159, 140]),
+        I2cTrans::write_read(0x5A, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write(0x5B, vec![Reg::CONFIG_1, 0, 0, 111]),
+        I2cTrans::write(0x5B, vec![Reg::CONFIG_1, 180, 159, 160]),
+        // read back a different value
+        I2cTrans::write_read(0x5B, vec![Reg::CONFIG_1], vec![176, 159, 109]),
+        I2cTrans::write(0x5C, vec![Reg::CONFIG_1, 0, 0, 171]),
+        I2cTrans::write(0x5C, vec![Reg::CONFIG_1, 180, 159, 100]),
+        I2cTrans::write_read(0x5C, vec![Reg::CONFIG_1], vec![180, 159, 71]),
+    ]);
+    let config = Config {
+        iir: Iir::Step100,
+        repeat_sensor_selftest: false,
+        pwm_mode: PwmMode::Tobj1Tobj2,
+        dual_ir_sensor: false,
+        ks_sign_negative: true,
+        fir: Fir::Step1024,
+        gain: Gain::Gain12_5,
+        kt2_sign_negative: false,
+        sensor_selftest_disabled: true,
+    };
+    let results = configure_all(&mut i2c, &[0x5A, 0x5B, 0x5C], &config, 5, &mut NoopDelay {});
+    assert_eq!(3, results.len());
+    assert!(results[0].is_ok());
+    assert_error!(results[1], BadEepromWrite);
+    assert!(results[2].is_ok());
+    i2c.done();
+}
+
+#[test]
+fn can_setup_pwm() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::PWMCTRL], vec![1, 2, 157]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::TOMIN, 0, 0, 232]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::TOMIN, 180, 106, 226]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOMIN], vec![180, 106, 182]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::TOMAX, 0, 0, 131]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::TOMAX, 196, 145, 196]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOMAX], vec![196, 145, 237]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::PWMCTRL, 0, 0, 85]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::PWMCTRL, 2, 10, 73]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::PWMCTRL], vec![2, 10, 154]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 0, 0, 67]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 132, 159, 117]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![132, 159, 210]),
+    ]);
+    sensor
+        .setup_pwm(
+            PwmMode::TaTobj1,
+            Temperature::from_raw(13658),
+            Temperature::from_raw(18658),
+            10.24,
+            &mut NoopDelay {},
+        )
+        .unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn write_protected_modify_config_1_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    sensor.set_eeprom_write_protect(true);
+    assert_error!(
+        sensor.modify_config_1(&mut NoopDelay {}, |config| config.dual_ir_sensor = true),
+        WriteProtected
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn write_protected_setup_pwm_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    sensor.set_eeprom_write_protect(true);
+    assert_error!(
+        sensor.setup_pwm(
+            PwmMode::TaTobj1,
+            Temperature::from_raw(13658),
+            Temperature::from_raw(18658),
+            10.24,
+            &mut NoopDelay {},
+        ),
+        WriteProtected
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn write_protected_set_relay_hysteresis_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    sensor.set_eeprom_write_protect(true);
+    assert_error!(
+        sensor.set_relay_hysteresis(
+            Temperature::from_raw(15158),
+            Temperature::from_raw(15658),
+            &mut NoopDelay {},
+        ),
+        WriteProtected
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn write_protected_calibrate_write_delay_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    sensor.set_eeprom_write_protect(true);
+    assert_error!(
+        sensor.calibrate_write_delay(&mut NoopDelay {}, 1, 10),
+        WriteProtected
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn can_set_relay_hysteresis() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::PWMCTRL], vec![1, 2, 157]),
+        // 30°C
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::TOMIN, 0, 0, 232]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::TOMIN, 108, 118, 164]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOMIN], vec![108, 118, 240]),
+        // 40°C
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::TOMAX, 0, 0, 131]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::TOMAX, 84, 122, 186]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOMAX], vec![84, 122, 147]),
+        // PWM enabled, thermal relay mode
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::PWMCTRL, 0, 0, 85]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::PWMCTRL, 11, 2, 204]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::PWMCTRL], vec![11, 2, 31]),
+    ]);
+    sensor
+        .set_relay_hysteresis(
+            Temperature::from_raw(15158),
+            Temperature::from_raw(15658),
+            &mut NoopDelay {},
+        )
+        .unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn set_relay_hysteresis_wrong_range_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(
+        sensor.set_relay_hysteresis(
+            Temperature::from_raw(15658),
+            Temperature::from_raw(15158),
+            &mut NoopDelay {},
+        ),
+        InvalidInputData
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn setup_pwm_wrong_range_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(
+        sensor.setup_pwm(
+            PwmMode::TaTobj1,
+            Temperature::from_raw(18658),
+            Temperature::from_raw(13658),
+            10.24,
+            &mut NoopDelay {},
+        ),
+        InvalidInputData
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn setup_pwm_wrong_period_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(
+        sensor.setup_pwm(
+            PwmMode::Tobj2,
+            Temperature::from_raw(13658),
+            Temperature::from_raw(18658),
+            200.0,
+            &mut NoopDelay {},
+        ),
+        InvalidInputData
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_ambient_temperature_ranged() {
+    let mut sensor = new_mlx90614(&[
+        // 24.57°C
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        // -0.7°C - 24.3°C
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TARANGE], vec![24, 40, 183]),
+        // 19.99°C, TARANGE cached
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![65, 57, 241]),
+    ]);
+    let t = sensor.ambient_temperature_ranged().unwrap();
+    assert_near!(t.celsius(), 24.3, 0.02);
+    let t = sensor.ambient_temperature_ranged().unwrap();
+    assert_near!(t.celsius(), 19.99, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn adaptive_sample_speeds_up_on_change() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        // +5.12°C
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 60, 178]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 60, 178]),
+    ]);
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::delay_ms(1000),
+        DelayTrans::delay_ms(1000),
+        DelayTrans::delay_ms(100),
+    ]);
+    let mut samples = Vec::new();
+    sensor
+        .adaptive_sample(&mut delay, 1000, 100, 1.0, |t| {
+            samples.push(t.raw());
+            samples.len() < 4
+        })
+        .unwrap();
+    assert_eq!(vec![0x3B00, 0x3B00, 0x3C00, 0x3C00], samples);
+    delay.done();
+    destroy(sensor);
+}
+
+#[test]
+fn object1_smoothed_converges_after_step() {
+    let mut transactions = vec![I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::TOBJ1],
+        vec![0, 59, 167],
+    )];
+    // step to 34.05°C
+    for _ in 0..8 {
+        transactions.push(I2cTrans::write_read(
+            mlx90614::DEV_ADDR,
+            vec![Reg::TOBJ1],
+            vec![0, 60, 178],
+        ));
+    }
+    let mut sensor = new_mlx90614(&transactions);
+    let t = sensor.object1_smoothed(0.5).unwrap();
+    assert_near!(t, 28.93, 0.01);
+    let t = sensor.object1_smoothed(0.5).unwrap();
+    assert_near!(t, 31.49, 0.01);
+    for _ in 0..6 {
+        sensor.object1_smoothed(0.5).unwrap();
+    }
+    // 34.05 - 5.12 / 2^8
+    let t = sensor.object1_smoothed(0.5).unwrap();
+    assert_near!(t, 34.03, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn object1_smoothed_with_invalid_alpha_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(sensor.object1_smoothed(-0.1), InvalidInputData);
+    assert_error!(sensor.object1_smoothed(1.1), InvalidInputData);
+    destroy(sensor);
+}
+
+#[test]
+fn object1_peak_hold_keeps_maximum() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![254, 58, 98]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![2, 59, 141]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![254, 58, 98]),
+    ]);
+    assert_eq!(
+        Temperature::from_raw(0x3AFE),
+        sensor.object1_peak_hold().unwrap()
+    );
+    assert_eq!(
+        Temperature::from_raw(0x3B02),
+        sensor.object1_peak_hold().unwrap()
+    );
+    assert_eq!(
+        Temperature::from_raw(0x3B02),
+        sensor.object1_peak_hold().unwrap()
+    );
+    sensor.reset_peak();
+    assert_eq!(
+        Temperature::from_raw(0x3AFE),
+        sensor.object1_peak_hold().unwrap()
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn reset_cache_clears_peak() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![2, 59, 141]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![254, 58, 98]),
+    ]);
+    assert_eq!(
+        Temperature::from_raw(0x3B02),
+        sensor.object1_peak_hold().unwrap()
+    );
+    sensor.reset_cache();
+    assert_eq!(
+        Temperature::from_raw(0x3AFE),
+        sensor.object1_peak_hold().unwrap()
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn thermostat_object1_applies_hysteresis() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![2, 59, 141]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![254, 58, 98]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+    ]);
+    let on_above = Temperature::from_raw(0x3B01);
+    let off_below = Temperature::from_raw(0x3AFF);
+    // in the band, initially off
+    assert!(!sensor.thermostat_object1(on_above, off_below).unwrap());
+    // above
+    assert!(sensor.thermostat_object1(on_above, off_below).unwrap());
+    // in the band, held on
+    assert!(sensor.thermostat_object1(on_above, off_below).unwrap());
+    // below
+    assert!(!sensor.thermostat_object1(on_above, off_below).unwrap());
+    // in the band, held off
+    assert!(!sensor.thermostat_object1(on_above, off_below).unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn thermostat_object1_with_inverted_bounds_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(
+        sensor.thermostat_object1(Temperature::from_raw(0x3AFF), Temperature::from_raw(0x3B01)),
+        InvalidInputData
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn can_wait_until_object1_above() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![254, 58, 98]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![2, 59, 141]),
+    ]);
+    let mut delay = CheckedDelay::new(&[DelayTrans::delay_ms(10), DelayTrans::delay_ms(10)]);
+    let t = sensor
+        .wait_until_object1_above(Temperature::from_raw(0x3B01), &mut delay, 10, 100)
+        .unwrap();
+    assert_eq!(Temperature::from_raw(0x3B02), t);
+    delay.done();
+    destroy(sensor);
+}
+
+#[test]
+fn wait_until_object1_above_times_out() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![254, 58, 98]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+    ]);
+    assert_error!(
+        sensor.wait_until_object1_above(Temperature::from_raw(0x3B01), &mut NoopDelay {}, 10, 15),
+        Timeout
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn wait_until_object1_above_zero_interval_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(
+        sensor.wait_until_object1_above(Temperature::from_raw(0x3B01), &mut NoopDelay {}, 0, 15),
+        InvalidInputData
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn can_get_ambient_trend() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![43, 58, 143]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![48, 58, 79]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![53, 58, 14]),
+    ]);
+    let trend = sensor.ambient_trend(4, &mut NoopDelay {}).unwrap();
+    // 5 raw counts per sample
+    assert_near!(trend, 0.1, 0.0001);
+    destroy(sensor);
+}
+
+#[test]
+fn ambient_trend_too_few_samples_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(sensor.ambient_trend(1, &mut NoopDelay {}), InvalidInputData);
+    destroy(sensor);
+}
+
+/// Readings of a 28.93°C target at 24.57°C with RAW_IR1 = 50 and emissivity 1.0
+fn ir_scale_transactions() -> [I2cTrans; 4] {
+    [
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![50, 0, 239]),
+        I2cTrans::write_read(
+            mlx90614::DEV_ADDR,
+            vec![Reg::EMISSIVITY],
+            vec![255, 255, 214],
+        ),
+    ]
+}
+
+#[test]
+fn can_get_ir_scale() {
+    let mut sensor = new_mlx90614(&ir_scale_transactions());
+    // (302.08^4 - 297.72^4) / 50
+    let scale = sensor.ir_scale().unwrap();
+    assert_near!(scale, 9_408_683.0, 100.0);
+    // cached
+    let scale = sensor.ir_scale().unwrap();
+    assert_near!(scale, 9_408_683.0, 100.0);
+    destroy(sensor);
+}
+
+#[test]
+fn ir_scale_with_zero_raw_ir_returns_error() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![0, 0, 60]),
+        I2cTrans::write_read(
+            mlx90614::DEV_ADDR,
+            vec![Reg::EMISSIVITY],
+            vec![255, 255, 214],
+        ),
+    ]);
+    assert_error!(sensor.refresh_ir_scale(), InvalidInputData);
+    destroy(sensor);
+}
+
+#[test]
+fn compensation_residual_is_zero_when_deriving_scale() {
+    let mut sensor = new_mlx90614(&ir_scale_transactions());
+    let residual = sensor.compensation_residual().unwrap();
+    assert_near!(residual, 0.0, 0.001);
+    destroy(sensor);
+}
+
+#[test]
+fn can_get_compensation_residual() {
+    let mut transactions = ir_scale_transactions().to_vec();
+    transactions.extend([
+        // 28.97°C
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![2, 59, 141]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![50, 0, 239]),
+    ]);
+    let mut sensor = new_mlx90614(&transactions);
+    sensor.refresh_ir_scale().unwrap();
+    let residual = sensor.compensation_residual().unwrap();
+    // 28.97 - 28.93
+    assert_near!(residual, 0.04, 0.005);
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_pair_into() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+    ]);
+    let mut out = [0; 2];
+    sensor.read_pair_into(&mut out).unwrap();
+    assert_eq!([0x3A26, 0x3B00], out);
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_object1_median() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![2, 59, 141]),
+        // outlier
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 64, 193]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![254, 58, 98]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![1, 59, 178]),
+    ]);
+    assert_eq!(
+        Temperature::from_raw(0x3B01),
+        sensor.object1_median(5).unwrap()
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_object1_filtered() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        // outlier
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 64, 193]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![2, 59, 141]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![1, 59, 178]),
+    ]);
+    sensor.set_filter_window(3).unwrap();
+    let expected = [0x3B00, 0x3B00, 0x3B02, 0x3B02];
+    for raw in expected {
+        assert_eq!(
+            Temperature::from_raw(raw),
+            sensor.object1_filtered().unwrap()
+        );
+    }
+    destroy(sensor);
+}
+
+#[test]
+fn set_wrong_filter_window_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(sensor.set_filter_window(0), InvalidInputData);
+    assert_error!(sensor.set_filter_window(16), InvalidInputData);
+    destroy(sensor);
+}
+
+#[test]
+fn object1_median_even_samples_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(sensor.object1_median(4), InvalidInputData);
+    assert_error!(sensor.object1_median(0), InvalidInputData);
+    destroy(sensor);
+}
+
+#[test]
+fn object1_median_too_many_samples_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(sensor.object1_median(17), InvalidInputData);
+    destroy(sensor);
+}
+
+macro_rules! raw_ir_percent_test {
+    ($name:ident, $data:expr, $expected:expr) => {
+        #[test]
+        fn $name() {
+            let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+                mlx90614::DEV_ADDR,
+                vec![Reg::RAW_IR1],
+                $data,
+            )]);
+            let percent = sensor.raw_ir_channel1_percent().unwrap();
+            assert_near!(percent, $expected, 0.001);
+            destroy(sensor);
+        }
+    };
+}
+
+raw_ir_percent_test!(raw_ir_percent_min, vec![255, 255, 24], 0.0);
+raw_ir_percent_test!(raw_ir_percent_zero, vec![0, 0, 60], 50.0);
+raw_ir_percent_test!(raw_ir_percent_max, vec![255, 127, 145], 100.0);
+
+#[test]
+fn can_read_raw_ir_difference() {
+    let mut sensor = new_mlx90614(&[
+        // -300
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![44, 129, 224]),
+        // 200
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR2], vec![200, 0, 111]),
+    ]);
+    assert_eq!(-500, sensor.raw_ir_difference().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn object2_temperature_opt_dual_sensor_returns_some() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![244, 159, 112]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ2], vec![0, 59, 117]),
+    ]);
+    let t = sensor.object2_temperature_opt().unwrap();
+    assert_eq!(Some(Temperature::from_raw(0x3B00)), t);
+    destroy(sensor);
+}
+
+#[test]
+fn object2_temperature_opt_single_sensor_returns_none() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::CONFIG_1],
+        vec![180, 159, 43],
+    )]);
+    assert_eq!(None, sensor.object2_temperature_opt().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_raw_ir_ratio() {
+    let mut sensor = new_mlx90614(&[
+        // -300
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![44, 129, 224]),
+        // 200
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR2], vec![200, 0, 111]),
+    ]);
+    let ratio = sensor.raw_ir_ratio().unwrap();
+    assert_near!(ratio, -1.5, 0.0001);
+    destroy(sensor);
+}
+
+#[test]
+fn raw_ir_ratio_zero_channel2_returns_error() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![44, 129, 224]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR2], vec![0, 0, 42]),
+    ]);
+    assert_error!(sensor.raw_ir_ratio(), InvalidInputData);
+    destroy(sensor);
+}
+
+fn object1_transaction() -> [I2cTrans; 1] {
+    // 302.08K
+    [I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::TOBJ1],
+        vec![0, 59, 167],
+    )]
+}
+
+#[test]
+fn object1_celsius_or_nan_returns_value() {
+    let mut sensor = new_mlx90614(&object1_transaction());
+    let t = sensor.object1_celsius_or_nan().unwrap();
+    assert_near!(t, 28.93, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn object1_celsius_or_nan_flagged_returns_nan() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::TOBJ1],
+        vec![0, 187, 46],
+    )]);
+    assert!(sensor.object1_celsius_or_nan().unwrap().is_nan());
+    destroy(sensor);
+}
+
+macro_rules! confidence_test {
+    ($name:ident, $tobj1:expr, $ir:expr, $expected_raw:expr, $expected:ident) => {
+        #[test]
+        fn $name() {
+            let mut sensor = new_mlx90614(&[
+                I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], $tobj1),
+                I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], $ir),
+            ]);
+            let (t, confidence) = sensor.object1_with_confidence().unwrap();
+            assert_eq!(Temperature::from_raw($expected_raw), t);
+            assert_eq!(Confidence::$expected, confidence);
+            destroy(sensor);
+        }
+    };
+}
+
+confidence_test!(
+    high_confidence,
+    vec![0, 59, 167],
+    vec![50, 0, 239],
+    0x3B00,
+    High
+);
+confidence_test!(
+    medium_confidence_near_saturation,
+    vec![0, 59, 167],
+    vec![0, 100, 7],
+    0x3B00,
+    Medium
+);
+confidence_test!(
+    low_confidence_saturated,
+    vec![0, 59, 167],
+    vec![255, 127, 145],
+    0x3B00,
+    Low
+);
+confidence_test!(
+    low_confidence_flagged,
+    vec![0, 187, 46],
+    vec![50, 0, 239],
+    0x3B00,
+    Low
+);
+
+macro_rules! reading_test {
+    ($name:ident, $tobj1:expr, $ir:expr, $expected:expr) => {
+        #[test]
+        fn $name() {
+            let mut sensor = new_mlx90614(&[
+                I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], $tobj1),
+                I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], $ir),
+            ]);
+            assert_eq!($expected, sensor.object1_reading().unwrap());
+            destroy(sensor);
+        }
+    };
+}
+
+reading_test!(
+    valid_reading,
+    vec![0, 59, 167],
+    vec![50, 0, 239],
+    Reading::Valid(Temperature::from_raw(0x3B00))
+);
+reading_test!(
+    flagged_reading,
+    vec![0, 187, 46],
+    vec![50, 0, 239],
+    Reading::Flagged(Temperature::from_raw(0x3B00))
+);
+reading_test!(
+    saturated_reading,
+    vec![0, 59, 167],
+    vec![255, 127, 145],
+    Reading::Saturated
+);
+reading_test!(
+    saturated_negative_reading,
+    vec![0, 59, 167],
+    vec![255, 255, 24],
+    Reading::Saturated
+);
+
+#[test]
+fn can_read_object1_in_celsius() {
+    let mut sensor = new_mlx90614(&object1_transaction());
+    let Celsius(t) = sensor.read_object1::<Celsius>().unwrap();
+    assert_near!(t, 28.93, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_object1_in_fahrenheit() {
+    let mut sensor = new_mlx90614(&object1_transaction());
+    let Fahrenheit(t) = sensor.read_object1::<Fahrenheit>().unwrap();
+    assert_near!(t, 84.074, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_object1_in_kelvin() {
+    let mut sensor = new_mlx90614(&object1_transaction());
+    let Kelvin(t) = sensor.read_object1::<Kelvin>().unwrap();
+    assert_near!(t, 302.08, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn junction_temperature_matches_ambient() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+    ]);
+    let junction = sensor.junction_temperature().unwrap();
+    assert_eq!(sensor.ambient_temperature().unwrap(), junction);
+    destroy(sensor);
+}
+
+#[test]
+fn plausible_ambient_is_connected() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::TA],
+        vec![38, 58, 102],
+    )]);
+    assert!(sensor.is_connected().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn stuck_ambient_is_not_connected() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![0, 0, 16]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![255, 255, 52]),
+    ]);
+    assert!(!sensor.is_connected().unwrap());
+    assert!(!sensor.is_connected().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn object1_celsius_is_uncalibrated_by_default() {
+    let mut sensor = new_mlx90614(&object1_transaction());
+    let t = sensor.object1_celsius().unwrap();
+    assert_near!(t, 28.93, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_object1_linearized() {
+    let mut sensor = new_mlx90614(&object1_transaction());
+    let lut = [(0.0, 0.0), (20.0, 21.0), (40.0, 43.0)];
+    let t = sensor.object1_linearized(&lut).unwrap();
+    // 21 + (28.93 - 20) * 22 / 20
+    assert_near!(t, 30.823, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn object1_linearized_extrapolates_outside_of_table() {
+    let mut sensor = new_mlx90614(&object1_transaction());
+    let lut = [(0.0, 0.0), (10.0, 12.0)];
+    let t = sensor.object1_linearized(&lut).unwrap();
+    assert_near!(t, 34.716, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn object1_linearized_with_invalid_table_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(sensor.object1_linearized(&[(0.0, 0.0)]), InvalidInputData);
+    assert_error!(
+        sensor.object1_linearized(&[(20.0, 21.0), (10.0, 12.0)]),
+        InvalidInputData
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn can_calibrate_two_point() {
+    let mut sensor = new_mlx90614(&object1_transaction());
+    sensor.calibrate_two_point(0.0, 1.0, 100.0, 99.0).unwrap();
+    let t = sensor.object1_celsius().unwrap();
+    // (28.93 - 1) * 100 / 98
+    assert_near!(t, 28.5, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn calibrate_two_point_with_equal_points_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(
+        sensor.calibrate_two_point(0.0, 1.0, 0.0, 99.0),
+        InvalidInputData
+    );
+    assert_error!(
+        sensor.calibrate_two_point(0.0, 1.0, 100.0, 1.0),
+        InvalidInputData
+    );
+    destroy(sensor);
+}
+
+macro_rules! object_average_test {
+    ($name:ident, $tobj1:expr, $tobj2:expr, $expected:expr) => {
+        #[test]
+        fn $name() {
+            let mut sensor = new_mlx90614(&[
+                I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], $tobj1),
+                I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ2], $tobj2),
+            ]);
+            let t = sensor.object_average().unwrap();
+            assert_eq!(Temperature::from_raw($expected), t);
+            destroy(sensor);
+        }
+    };
+}
+
+object_average_test!(
+    object_average_both_valid,
+    vec![0, 59, 167],
+    vec![2, 59, 95],
+    0x3B01
+);
+object_average_test!(
+    object_average_object1_flagged,
+    vec![0, 187, 46],
+    vec![2, 59, 95],
+    0x3B02
+);
+object_average_test!(
+    object_average_object2_flagged,
+    vec![0, 59, 167],
+    vec![0, 187, 252],
+    0x3B00
+);
+
+#[test]
+fn object_average_both_flagged_returns_error() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 187, 46]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ2], vec![0, 187, 252]),
+    ]);
+    match sensor.object_average() {
+        Err(Error::BadRead(t)) => assert_eq!(Temperature::from_raw(0x3B00), t),
+        _ => panic!("Should have returned error."),
+    }
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_timestamped() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+    ]);
+    let mut ticks = 41;
+    let mut clock = || {
+        ticks += 1;
+        ticks
+    };
+    let (timestamp, ambient, object1) = sensor.read_timestamped(&mut clock).unwrap();
+    assert_eq!(42, timestamp);
+    assert_eq!(Temperature::from_raw(0x3A26), ambient);
+    assert_eq!(Temperature::from_raw(0x3B00), object1);
+    destroy(sensor);
+}
+
+#[test]
+fn can_get_fast_object_estimate() {
+    let mut transactions = ir_scale_transactions().to_vec();
+    transactions.extend([
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![50, 0, 239]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![100, 0, 157]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+    ]);
+    let mut sensor = new_mlx90614(&transactions);
+    // same raw IR as when deriving the scale
+    let estimate = sensor.fast_object_estimate().unwrap();
+    assert_near!(estimate, 28.93, 0.01);
+    // (297.72^4 + 2 * (302.08^4 - 297.72^4))^(1/4) - 273.15
+    let estimate = sensor.fast_object_estimate().unwrap();
+    assert_near!(estimate, 33.109, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn fast_object_estimate_reads_emissivity_once() {
+    let mut transactions = ir_scale_transactions().to_vec();
+    for _ in 0..2 {
+        transactions.extend([
+            I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![50, 0, 239]),
+            I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        ]);
+    }
+    let mut sensor = new_mlx90614(&transactions);
+    let t = sensor.fast_object_estimate().unwrap();
+    assert_near!(t, 28.93, 0.01);
+    let t = sensor.fast_object_estimate().unwrap();
+    assert_near!(t, 28.93, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn fast_object_estimate_uses_written_emissivity() {
+    let mut transactions = ir_scale_transactions().to_vec();
+    transactions.extend([
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 0, 40]),
+        // emissivity 0.5
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 128, 161]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![50, 0, 239]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+    ]);
+    let mut sensor = new_mlx90614(&transactions);
+    sensor.ir_scale().unwrap();
+    sensor.set_emissivity(0.5, &mut NoopDelay {}).unwrap();
+    // (297.72^4 + (302.08^4 - 297.72^4) * 1.0 / 0.5)^(1/4) - 273.15
+    let t = sensor.fast_object_estimate().unwrap();
+    assert_near!(t, 33.109, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn can_refresh_emissivity() {
+    let mut sensor = new_mlx90614(&[
+        // emissivity 0.5
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY], vec![0, 128, 123]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![50, 0, 239]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![50, 0, 239]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+    ]);
+    let epsilon = sensor.refresh_emissivity().unwrap();
+    assert_near!(epsilon, 0.5, 0.001);
+    let t = sensor.fast_object_estimate().unwrap();
+    assert_near!(t, 28.93, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_object1_celsius_as_if_emissivity() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        // emissivity 1.0
+        I2cTrans::write_read(
+            mlx90614::DEV_ADDR,
+            vec![Reg::EMISSIVITY],
+            vec![255, 255, 214],
+        ),
+    ]);
+    // (297.72^4 + (302.08^4 - 297.72^4) * 1.0 / 0.5)^(1/4) - 273.15
+    let t = sensor.object1_celsius_as_if_emissivity(0.5).unwrap();
+    assert_near!(t, 33.109, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn object1_celsius_as_if_actual_emissivity_is_unchanged() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        I2cTrans::write_read(
+            mlx90614::DEV_ADDR,
+            vec![Reg::EMISSIVITY],
+            vec![255, 255, 214],
+        ),
+    ]);
+    let t = sensor.object1_celsius_as_if_emissivity(1.0).unwrap();
+    assert_near!(t, 28.93, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn object1_celsius_as_if_invalid_emissivity_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(
+        sensor.object1_celsius_as_if_emissivity(0.0),
+        InvalidInputData
+    );
+    assert_error!(
+        sensor.object1_celsius_as_if_emissivity(1.1),
+        InvalidInputData
+    );
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_object1_with_ambient_override() {
+    let mut transactions = ir_scale_transactions().to_vec();
+    transactions.push(I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::RAW_IR1],
+        vec![50, 0, 239],
+    ));
+    let mut sensor = new_mlx90614(&transactions);
+    let t = sensor.object1_with_ambient_override(20.0, 0.5).unwrap();
+    // (293.15^4 + (302.08^4 - 297.72^4) / 0.5)^(1/4) - 273.15
+    assert_near!(t, 28.921, 0.01);
+    destroy(sensor);
+}
+
+#[test]
+fn object1_with_ambient_override_wrong_emissivity_returns_error() {
+    let mut sensor = new_mlx90614(&[]);
+    assert_error!(
+        sensor.object1_with_ambient_override(20.0, 0.0),
+        InvalidInputData
+    );
+    destroy(sensor);
+}
+
+fn target_presence_transactions() -> [I2cTrans; 2] {
+    [
+        // 28.93°C
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![0, 59, 167]),
+        // 24.57°C
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+    ]
+}
+
+#[test]
+fn can_read_object1_minus_ambient_millicelsius() {
+    let mut sensor = new_mlx90614(&target_presence_transactions());
+    // 28.93°C - 24.57°C
+    assert_eq!(4360, sensor.object1_minus_ambient_millicelsius().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn target_present_above_threshold() {
+    let mut sensor = new_mlx90614(&target_presence_transactions());
+    assert!(sensor.target_present(4.0).unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn target_not_present_below_threshold() {
+    let mut sensor = new_mlx90614(&target_presence_transactions());
+    assert!(!sensor.target_present(5.0).unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_raw_ir_array() {
+    let mut i2c = I2cMock::new(&[
+        I2cTrans::write_read(0x5A, vec![Reg::RAW_IR1], vec![50, 0, 239]),
+        I2cTrans::write_read(0x5A, vec![Reg::RAW_IR2], vec![200, 0, 111]),
+        I2cTrans::write_read(0x5B, vec![Reg::RAW_IR1], vec![44, 129, 242]),
+        I2cTrans::write_read(0x5B, vec![Reg::RAW_IR2], vec![0, 0, 56]),
+    ]);
+    let [first, second] = read_raw_ir_array(&mut i2c, &[0x5A, 0x5B]);
+    assert_eq!((50, 200), first.unwrap());
+    assert_eq!((-300, 0), second.unwrap());
+    i2c.done();
+}
+
+#[test]
+fn can_get_ambient_spread() {
+    let mut i2c = I2cMock::new(&[
+        I2cTrans::write_read(0x5A, vec![Reg::TA], vec![38, 58, 102]),
+        I2cTrans::write_read(0x5B, vec![Reg::TA], vec![97, 58, 68]),
+        // divergent ambient
+        I2cTrans::write_read(0x5C, vec![Reg::TA], vec![0, 60, 200]),
+    ]);
+    let spread = ambient_spread(&mut i2c, &[0x5A, 0x5B, 0x5C]).unwrap();
+    assert_near!(spread, 9.48, 0.01);
+    i2c.done();
+}
+
+#[test]
+fn ambient_spread_without_addresses_returns_error() {
+    let mut i2c = I2cMock::new(&[]);
+    assert_error!(ambient_spread(&mut i2c, &[]), InvalidInputData);
+    i2c.done();
+}
+
+read_f32_test!(read_emiss, emissivity, Reg::EMISSIVITY, 51, 179, 36, 0.7);
+
+#[test]
+fn can_get_single_zone_variant_name() {
+    let mut transactions = device_id_transactions();
+    transactions.push(I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::CONFIG_1],
+        vec![180, 159, 43],
+    ));
+    let mut sensor = new_mlx90614(&transactions);
+    assert_eq!("MLX90614xAx", sensor.variant_name().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_get_dual_zone_variant_name() {
+    let mut transactions = device_id_transactions();
+    transactions.push(I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::CONFIG_1],
+        vec![244, 159, 112],
+    ));
+    let mut sensor = new_mlx90614(&transactions);
+    assert_eq!("MLX90614xBx", sensor.variant_name().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn unprogrammed_id_variant_name_is_unknown() {
+    let mut sensor = new_mlx90614(&[
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::ID0], vec![0, 0, 37]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::ID0 + 1], vec![0, 0, 51]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::ID0 + 2], vec![0, 0, 9]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::ID0 + 3], vec![0, 0, 31]),
+    ]);
+    assert_eq!("Unknown", sensor.variant_name().unwrap());
+    destroy(sensor);
+}
+
+fn device_id_transactions() -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::ID0], vec![0x34, 0x12, 246]),
+        I2cTrans::write_read(
+            mlx90614::DEV_ADDR,
+            vec![Reg::ID0 + 1],
+            vec![0x78, 0x56, 156],
+        ),
+        I2cTrans::write_read(
+            mlx90614::DEV_ADDR,
+            vec![Reg::ID0 + 2],
+            vec![0xBC, 0x9A, 117],
+        ),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::ID0 + 3], vec![0xF0, 0xDE, 31]),
+    ]
+}
+
+#[test]
+fn can_get_id() {
+    let mut sensor = new_mlx90614(&device_id_transactions());
+    assert_eq!(0x1234_5678_9ABC_DEF0, sensor.device_id().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn device_id_is_cached() {
+    let mut sensor = new_mlx90614(&device_id_transactions());
+    assert_eq!(0x1234_5678_9ABC_DEF0, sensor.device_id().unwrap());
+    assert_eq!(0x1234_5678_9ABC_DEF0, sensor.device_id().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_refresh_device_id() {
+    let mut transactions = device_id_transactions();
+    transactions.extend(device_id_transactions());
+    let mut sensor = new_mlx90614(&transactions);
+    assert_eq!(0x1234_5678_9ABC_DEF0, sensor.device_id().unwrap());
+    assert_eq!(0x1234_5678_9ABC_DEF0, sensor.refresh_device_id().unwrap());
+    assert_eq!(0x1234_5678_9ABC_DEF0, sensor.device_id().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn device_id_is_read_again_after_cache_reset() {
+    let mut transactions = device_id_transactions();
+    transactions.extend(device_id_transactions());
+    let mut sensor = new_mlx90614(&transactions);
+    assert_eq!(0x1234_5678_9ABC_DEF0, sensor.device_id().unwrap());
+    sensor.reset_cache();
+    assert_eq!(0x1234_5678_9ABC_DEF0, sensor.device_id().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_get_id_be() {
+    let mut sensor = new_mlx90614(&device_id_transactions());
+    assert_eq!(0x1234_5678_9ABC_DEF0, sensor.device_id_be().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_get_id_le() {
+    let mut sensor = new_mlx90614(&device_id_transactions());
+    assert_eq!(0xDEF0_9ABC_5678_1234, sensor.device_id_le().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_flags() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::FLAGS],
+        vec![0x90, 0, 134],
+    )]);
+    let flags = sensor.flags().unwrap();
+    assert!(flags.eeprom_busy);
+    assert!(!flags.eeprom_dead);
+    assert!(!flags.init_ongoing);
+    destroy(sensor);
+}
+
+fn busy_write_delay_transactions() -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 0, 40]),
+        // EEPROM busy
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::FLAGS], vec![144, 0, 134]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 255, 255, 12]),
+    ]
+}
+
+#[test]
+fn can_calibrate_write_delay() {
+    let mut transactions = vec![I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::EMISSIVITY],
+        vec![255, 255, 214],
+    )];
+    // 1ms and 2ms are too short
+    transactions.extend(busy_write_delay_transactions());
+    transactions.extend(busy_write_delay_transactions());
+    transactions.extend([
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 0, 40]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::FLAGS], vec![16, 0, 48]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 255, 255, 12]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::FLAGS], vec![16, 0, 48]),
+        I2cTrans::write_read(
+            mlx90614::DEV_ADDR,
+            vec![Reg::EMISSIVITY],
+            vec![255, 255, 214],
+        ),
+        // the calibrated delay is used afterwards
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 0, 0, 67]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1, 180, 159, 140]),
+    ]);
+    let mut sensor = new_mlx90614(&transactions);
+    let delay_ms = sensor
+        .calibrate_write_delay(&mut NoopDelay {}, 1, 5)
+        .unwrap();
+    assert_eq!(3, delay_ms);
+    let mut delay = CheckedDelay::new(&[DelayTrans::delay_ms(3)]);
+    sensor
+        .set_config_1_unchecked(Config::default(), &mut delay)
+        .unwrap();
+    delay.done();
+    destroy(sensor);
+}
+
+#[test]
+fn calibrate_write_delay_without_success_returns_error() {
+    let mut transactions = vec![I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::EMISSIVITY],
+        vec![255, 255, 214],
+    )];
+    transactions.extend(busy_write_delay_transactions());
+    transactions.extend(emissivity_restore_transactions());
+    let mut sensor = new_mlx90614(&transactions);
+    assert_error!(
+        sensor.calibrate_write_delay(&mut NoopDelay {}, 2, 2),
+        BadEepromWrite
+    );
+    destroy(sensor);
+}
+
+/// Best-effort restore of emissivity 1.0 after an error
+fn emissivity_restore_transactions() -> [I2cTrans; 2] {
+    [
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 0, 40]),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 255, 255, 12]),
+    ]
+}
+
+#[test]
+fn calibrate_write_delay_restores_emissivity_on_read_error() {
+    let mut transactions = vec![
+        I2cTrans::write_read(
+            mlx90614::DEV_ADDR,
+            vec![Reg::EMISSIVITY],
+            vec![255, 255, 214],
+        ),
+        I2cTrans::write(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 0, 40]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::FLAGS], vec![16, 0, 48])
+            .with_error(ErrorKind::Other),
+    ];
+    transactions.extend(emissivity_restore_transactions());
+    let mut sensor = new_mlx90614(&transactions);
+    match sensor.calibrate_write_delay(&mut NoopDelay {}, 1, 5) {
+        Err(Error::I2C(ErrorKind::Other)) => (),
+        _ => panic!("Should have returned error."),
+    }
+    destroy(sensor);
+}
+
+#[cfg(feature = "embedded-io")]
+#[test]
+fn can_stream_raw_ir() {
+    struct Collector(Vec<u8>);
+
+    impl embedded_io::ErrorType for Collector {
+        type Error = core::convert::Infallible;
+    }
+
+    impl embedded_io::Write for Collector {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.0.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    let mut sensor = new_mlx90614(&[
+        // FIR 1024, single IR sensor
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![50, 0, 239]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR2], vec![200, 0, 111]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR1], vec![44, 129, 224]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::RAW_IR2], vec![0, 0, 42]),
+    ]);
+    let mut delay = CheckedDelay::new(&[DelayTrans::delay_us(93_000)]);
+    let mut writer = Collector(Vec::new());
+    sensor.stream_raw_ir(&mut writer, &mut delay, 2).unwrap();
+    // 50, 200, -300, 0
+    assert_eq!(vec![50, 0, 200, 0, 0xD4, 0xFE, 0, 0], writer.0);
+    delay.done();
+    destroy(sensor);
+}
+
+#[cfg(feature = "bitflags")]
+#[test]
+fn can_read_flag_bits() {
+    use mlx9061x::mlx90614::FlagBits;
+    let mut sensor = new_mlx90614(&[I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::FLAGS],
+        vec![0x90, 0, 134],
+    )]);
+    let flags = sensor.flag_bits().unwrap();
+    assert!(flags.contains(FlagBits::EE_BUSY | FlagBits::INIT));
+    assert!(!flags.intersects(FlagBits::EE_DEAD));
+    destroy(sensor);
+}
+
+#[test]
+fn can_read_status_report() {
+    let mut transactions = vec![I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::FLAGS],
+        vec![16, 0, 48],
+    )];
+    transactions.extend(device_id_transactions());
+    transactions.extend([
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::EMISSIVITY], vec![51, 179, 36]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TOBJ1], vec![97, 58, 64]),
+    ]);
+    let mut sensor = new_mlx90614(&transactions);
+    let report = sensor.status_report().unwrap();
+    assert_eq!(0x1234_5678_9ABC_DEF0, report.device_id);
+    assert_eq!(Iir::Step100, report.config.iir);
+    assert_eq!(Fir::Step1024, report.config.fir);
+    assert!(!report.config.dual_ir_sensor);
+    assert_near!(report.emissivity, 0.7, 0.01);
+    assert_near!(report.ambient.celsius(), 24.57, 0.1);
+    assert_near!(report.object1.celsius(), 25.75, 0.1);
+    assert_eq!(None, report.object2);
+    assert!(!report.flags.eeprom_busy);
+    assert!(!report.flags.eeprom_dead);
+    assert!(!report.flags.init_ongoing);
+    destroy(sensor);
+}
+
+#[test]
+fn can_sleep() {
+    let mut sensor = new_mlx90614(&[I2cTrans::write(
+        mlx90614::DEV_ADDR,
+        vec![mlx90614::SLEEP_COMMAND, 232],
+    )]);
+    sensor.sleep().unwrap();
     destroy(sensor);
 }
 
@@ -176,3 +2331,129 @@ fn can_wake() {
     scl.done();
     sda.done()
 }
+
+#[test]
+fn can_verify_matching_device_id() {
+    let mut sensor = new_mlx90614(&device_id_transactions());
+    sensor.verify_device_id(0x1234_5678_9ABC_DEF0).unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn verify_device_id_mismatch_returns_error() {
+    let mut sensor = new_mlx90614(&device_id_transactions());
+    assert_error!(
+        sensor.verify_device_id(0x1234_5678_9ABC_DEF1),
+        DeviceMismatch
+    );
+    destroy(sensor);
+}
+
+fn state_transactions(state: &DeviceState) -> Vec<I2cTrans> {
+    (0..0x40)
+        .map(|register| {
+            let frame = state.frame(mlx90614::DEV_ADDR, register).unwrap();
+            I2cTrans::write_read(mlx90614::DEV_ADDR, vec![register], frame.to_vec())
+        })
+        .collect()
+}
+
+#[test]
+fn can_read_register_map() {
+    let mut state = DeviceState {
+        ram: [0; 32],
+        eeprom: [0; 32],
+    };
+    state.ram[usize::from(Reg::TA)] = 0x3A26;
+    state.ram[usize::from(Reg::TOBJ1)] = 0x3B00;
+    state.eeprom[usize::from(Reg::EMISSIVITY - Reg::TOMAX)] = 0xFFFF;
+    state.eeprom[usize::from(Reg::CONFIG_1 - Reg::TOMAX)] = 0x9FB4;
+    let all = state_transactions(&state);
+    let mut transactions: Vec<I2cTrans> = [
+        Reg::RAW_IR1,
+        Reg::RAW_IR2,
+        Reg::TA,
+        Reg::TOBJ1,
+        Reg::TOBJ2,
+        Reg::TOMAX,
+        Reg::TOMIN,
+        Reg::PWMCTRL,
+        Reg::TARANGE,
+        Reg::EMISSIVITY,
+        Reg::CONFIG_1,
+        Reg::ADDRESS,
+        Reg::ID0,
+        Reg::ID0 + 1,
+        Reg::ID0 + 2,
+        Reg::ID0 + 3,
+    ]
+    .iter()
+    .map(|register| all[usize::from(*register)].clone())
+    .collect();
+    transactions.push(I2cTrans::write_read(
+        mlx90614::DEV_ADDR,
+        vec![Reg::FLAGS],
+        vec![16, 0, 48],
+    ));
+    let mut sensor = new_mlx90614(&transactions);
+    let map = sensor.register_map().unwrap();
+    assert_eq!(17, map.len());
+    assert_eq!(("TA", 0x3A26), map[2]);
+    assert_eq!(("TOBJ1", 0x3B00), map[3]);
+    assert_eq!(("EMISSIVITY", 0xFFFF), map[9]);
+    assert_eq!(("CONFIG_1", 0x9FB4), map[10]);
+    assert_eq!(("FLAGS", 0x10), map[16]);
+    destroy(sensor);
+}
+
+#[test]
+fn can_capture_and_replay_state() {
+    let mut expected = DeviceState {
+        ram: [0; 32],
+        eeprom: [0; 32],
+    };
+    expected.ram[usize::from(Reg::TA)] = 0x3A26;
+    expected.ram[usize::from(Reg::TOBJ1)] = 0x3B00;
+    expected.eeprom[usize::from(Reg::EMISSIVITY - Reg::TOMAX)] = 0xFFFF;
+    expected.eeprom[usize::from(Reg::CONFIG_1 - Reg::TOMAX)] = 0x9FB4;
+    let mut transactions = state_transactions(&expected);
+    // known frames
+    assert_eq!(
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::TA], vec![38, 58, 102]),
+        transactions[usize::from(Reg::TA)]
+    );
+    assert_eq!(
+        I2cTrans::write_read(mlx90614::DEV_ADDR, vec![Reg::CONFIG_1], vec![180, 159, 43]),
+        transactions[usize::from(Reg::CONFIG_1)]
+    );
+    transactions.extend(state_transactions(&expected));
+    let mut sensor = new_mlx90614(&transactions);
+    let state = sensor.capture_state().unwrap();
+    assert_eq!(expected, state);
+    // replay the captured state
+    assert_eq!(state, sensor.capture_state().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn device_state_frame_outside_range_is_none() {
+    let state = DeviceState {
+        ram: [0; 32],
+        eeprom: [0; 32],
+    };
+    assert_eq!(None, state.frame(mlx90614::DEV_ADDR, Reg::FLAGS));
+}
+
+#[test]
+fn can_convert_into_mlx90615() {
+    let i2c = I2cMock::new(&[I2cTrans::write_read(
+        0x5C,
+        vec![0x26], // MLX90615 TA
+        vec![38, 58, 196],
+    )]);
+    let sensor = Mlx9061x::new_mlx90614(i2c, SlaveAddr::Alternative(0x5C), 5).unwrap();
+    let mut sensor = sensor.into_mlx90615();
+    let t = sensor.ambient_temperature().unwrap();
+    assert_near!(t.celsius(), 24.57, 0.01);
+    sensor.destroy().done();
+}
@@ -2,10 +2,10 @@ mod base;
 use crate::base::{destroy, mlx90615, mlx90615::Register as Reg, new_mlx90615};
 use embedded_hal_mock::eh1::{
     delay::NoopDelay,
-    i2c::Transaction as I2cTrans,
-    pin::{Mock as PinMock, State as PinState, Transaction as PinTrans},
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
 };
-use mlx9061x::{mlx90615::wake_mlx90615, SlaveAddr};
+use mlx9061x::{mlx90615::wake_mlx90615, Error, Material, Mlx9061x, SlaveAddr};
 
 macro_rules! read_f32_test {
     ($name:ident, $method:ident, $reg:expr, $data0:expr, $data1:expr, $data2:expr, $expected:expr) => {
@@ -42,6 +42,67 @@ read_temp_test!(read_ta2, ambient_temperature, Reg::TA, 97, 58, 138, 25.75);
 read_temp_test!(read_ta3, ambient_temperature, Reg::TA, 107, 58, 8, 25.95);
 read_temp_test!(read_ta4, ambient_temperature, Reg::TA, 38, 58, 186, 24.57);
 
+#[test]
+fn can_read_validated_ambient_temperature() {
+    let mut sensor = new_mlx90615(&[I2cTrans::write_read(
+        mlx90615::DEV_ADDR,
+        vec![Reg::TA],
+        vec![38, 58, 186],
+    )]);
+    let t = sensor.ambient_temperature_validated().unwrap();
+    assert_near!(t.celsius(), 24.57, 0.1);
+    destroy(sensor);
+}
+
+#[test]
+fn validated_ambient_temperature_too_high_returns_error() {
+    let mut sensor = new_mlx90615(&[I2cTrans::write_read(
+        mlx90615::DEV_ADDR,
+        vec![Reg::TA],
+        vec![226, 72, 90],
+    )]);
+    match sensor.ambient_temperature_validated() {
+        Err(Error::OutOfRange(t)) => {
+            assert_near!(t.celsius(), 100.0, 0.1);
+        }
+        _ => panic!("Should have returned error."),
+    }
+    destroy(sensor);
+}
+
+#[test]
+fn junction_temperature_matches_ambient() {
+    let mut sensor = new_mlx90615(&[
+        I2cTrans::write_read(mlx90615::DEV_ADDR, vec![Reg::TA], vec![38, 58, 186]),
+        I2cTrans::write_read(mlx90615::DEV_ADDR, vec![Reg::TA], vec![38, 58, 186]),
+    ]);
+    let junction = sensor.junction_temperature().unwrap();
+    assert_eq!(sensor.ambient_temperature().unwrap(), junction);
+    destroy(sensor);
+}
+
+#[test]
+fn plausible_ambient_is_connected() {
+    let mut sensor = new_mlx90615(&[I2cTrans::write_read(
+        mlx90615::DEV_ADDR,
+        vec![Reg::TA],
+        vec![38, 58, 186],
+    )]);
+    assert!(sensor.is_connected().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn stuck_ambient_is_not_connected() {
+    let mut sensor = new_mlx90615(&[I2cTrans::write_read(
+        mlx90615::DEV_ADDR,
+        vec![Reg::TA],
+        vec![0, 0, 204],
+    )]);
+    assert!(!sensor.is_connected().unwrap());
+    destroy(sensor);
+}
+
 read_temp_test!(
     read_object_temp,
     object_temperature,
@@ -52,6 +113,20 @@ read_temp_test!(
     24.57
 );
 
+#[test]
+fn object2_is_unsupported_without_communication() {
+    // only the object temperature register is read
+    let mut sensor = new_mlx90615(&[I2cTrans::write_read(
+        mlx90615::DEV_ADDR,
+        vec![Reg::TOBJ],
+        vec![38, 58, 172],
+    )]);
+    let t = sensor.object_temperature().unwrap();
+    assert_near!(t.celsius(), 24.57, 0.1);
+    assert_error!(sensor.object2_temperature(), UnsupportedOperation);
+    destroy(sensor);
+}
+
 read_i16_test!(
     read_raw_ir,
     new_mlx90615,
@@ -76,6 +151,47 @@ fn can_change_address() {
     destroy(sensor);
 }
 
+read_i16_test!(
+    read_raw_ir_negative_max,
+    new_mlx90615,
+    mlx90615::DEV_ADDR,
+    raw_ir,
+    Reg::RAW_IR,
+    0xFF,
+    0xFF,
+    210,
+    -32767
+);
+
+read_i16_test!(
+    read_raw_ir_negative_one,
+    new_mlx90615,
+    mlx90615::DEV_ADDR,
+    raw_ir,
+    Reg::RAW_IR,
+    0x01,
+    0x80,
+    106,
+    -1
+);
+
+#[test]
+fn write_pec_includes_address() {
+    // The PEC of a write frame covers the address write byte, so the same
+    // command and data produce a different PEC on another address.
+    let mut sensor = Mlx9061x::new_mlx90615(
+        I2cMock::new(&[
+            I2cTrans::write(0x5C, vec![Reg::EMISSIVITY, 0, 0, 55]),
+            I2cTrans::write(0x5C, vec![Reg::EMISSIVITY, 205, 44, 247]),
+        ]),
+        SlaveAddr::Alternative(0x5C),
+        5,
+    )
+    .unwrap();
+    sensor.set_emissivity(0.7, &mut NoopDelay {}).unwrap();
+    destroy(sensor);
+}
+
 #[test]
 fn can_set_emissivity() {
     let mut sensor = new_mlx90615(&[
@@ -86,26 +202,114 @@ fn can_set_emissivity() {
     destroy(sensor);
 }
 
-read_f32_test!(read_emiss, emissivity, Reg::EMISSIVITY, 205, 44, 235, 0.7);
-
 #[test]
-fn can_get_id() {
+fn can_set_emissivity_robust_with_retry() {
     let mut sensor = new_mlx90615(&[
+        I2cTrans::write(mlx90615::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 0, 243]),
+        I2cTrans::write(mlx90615::DEV_ADDR, vec![Reg::EMISSIVITY, 205, 44, 51]),
+        I2cTrans::write_read(mlx90615::DEV_ADDR, vec![Reg::EMISSIVITY], vec![0, 0, 43]),
+        I2cTrans::write(mlx90615::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 0, 243]),
+        I2cTrans::write(mlx90615::DEV_ADDR, vec![Reg::EMISSIVITY, 205, 44, 51]),
         I2cTrans::write_read(
             mlx90615::DEV_ADDR,
-            vec![mlx90615::Register::ID0],
-            vec![0x34, 0x12, 6],
+            vec![Reg::EMISSIVITY],
+            vec![205, 44, 235],
         ),
+    ]);
+    sensor
+        .set_emissivity_robust(0.7, &mut NoopDelay {}, 3)
+        .unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn can_set_emissivity_material() {
+    let mut sensor = new_mlx90615(&[
+        I2cTrans::write(mlx90615::DEV_ADDR, vec![Reg::EMISSIVITY, 0, 0, 243]),
+        I2cTrans::write(mlx90615::DEV_ADDR, vec![Reg::EMISSIVITY, 184, 62, 174]),
+    ]);
+    sensor
+        .set_emissivity_material(Material::HumanSkin, &mut NoopDelay {})
+        .unwrap();
+    destroy(sensor);
+}
+
+read_f32_test!(read_emiss, emissivity, Reg::EMISSIVITY, 205, 44, 235, 0.7);
+
+#[test]
+fn can_read_emissivity_raw_bytes() {
+    // emissivity 0.7
+    let mut sensor = new_mlx90615(&[I2cTrans::write_read(
+        mlx90615::DEV_ADDR,
+        vec![Reg::EMISSIVITY],
+        vec![205, 44, 235],
+    )]);
+    assert_eq!([0x2C, 0xCD], sensor.emissivity_raw_bytes().unwrap());
+    destroy(sensor);
+}
+
+fn device_id_transactions() -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write_read(mlx90615::DEV_ADDR, vec![Reg::ID0], vec![0x34, 0x12, 6]),
         I2cTrans::write_read(
             mlx90615::DEV_ADDR,
-            vec![mlx90615::Register::ID0 + 1],
+            vec![Reg::ID0 + 1],
             vec![0x78, 0x56, 108],
         ),
-    ]);
+    ]
+}
+
+#[test]
+fn can_get_id() {
+    let mut sensor = new_mlx90615(&device_id_transactions());
+    assert_eq!(0x1234_5678, sensor.device_id().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn device_id_is_cached() {
+    let mut sensor = new_mlx90615(&device_id_transactions());
+    assert_eq!(0x1234_5678, sensor.device_id().unwrap());
+    assert_eq!(0x1234_5678, sensor.device_id().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_refresh_device_id() {
+    let mut transactions = device_id_transactions();
+    transactions.extend(device_id_transactions());
+    let mut sensor = new_mlx90615(&transactions);
+    assert_eq!(0x1234_5678, sensor.device_id().unwrap());
+    assert_eq!(0x1234_5678, sensor.refresh_device_id().unwrap());
+    assert_eq!(0x1234_5678, sensor.device_id().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn device_id_is_read_again_after_cache_reset() {
+    let mut transactions = device_id_transactions();
+    transactions.extend(device_id_transactions());
+    let mut sensor = new_mlx90615(&transactions);
+    assert_eq!(0x1234_5678, sensor.device_id().unwrap());
+    sensor.reset_cache();
     assert_eq!(0x1234_5678, sensor.device_id().unwrap());
     destroy(sensor);
 }
 
+#[test]
+fn can_get_id_be() {
+    let mut sensor = new_mlx90615(&device_id_transactions());
+    assert_eq!(0x1234_5678, sensor.device_id_be().unwrap());
+    destroy(sensor);
+}
+
+#[test]
+fn can_get_id_le() {
+    let mut sensor = new_mlx90615(&device_id_transactions());
+    assert_eq!(0x5678_1234, sensor.device_id_le().unwrap());
+    destroy(sensor);
+}
+
 #[test]
 fn can_sleep() {
     let mut sensor = new_mlx90615(&[I2cTrans::write(
@@ -123,3 +327,17 @@ fn can_wake() {
     wake_mlx90615(&mut scl, &mut delay).unwrap();
     scl.done()
 }
+
+#[test]
+fn can_verify_matching_device_id() {
+    let mut sensor = new_mlx90615(&device_id_transactions());
+    sensor.verify_device_id(0x1234_5678).unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn verify_device_id_mismatch_returns_error() {
+    let mut sensor = new_mlx90615(&device_id_transactions());
+    assert_error!(sensor.verify_device_id(0x8765_4321), DeviceMismatch);
+    destroy(sensor);
+}